@@ -1085,6 +1085,7 @@ pub fn path2url<P: AsRef<Path>>(p: P) -> Url {
 struct RustcInfo {
     verbose_version: String,
     host: String,
+    release: String,
 }
 
 impl RustcInfo {
@@ -1100,9 +1101,19 @@ impl RustcInfo {
             .next()
             .expect("verbose version has host: field")
             .to_string();
+        let release = verbose_version
+            .lines()
+            .filter_map(|line| line.strip_prefix("release: "))
+            .next()
+            .expect("verbose version has release: field")
+            .split('-')
+            .next()
+            .unwrap()
+            .to_string();
         RustcInfo {
             verbose_version,
             host,
+            release,
         }
     }
 }
@@ -1116,6 +1127,11 @@ pub fn rustc_host() -> &'static str {
     &RUSTC_INFO.host
 }
 
+/// The rustc release without any pre-release suffix, such as `1.60.0`.
+pub fn rustc_release() -> &'static str {
+    &RUSTC_INFO.release
+}
+
 /// The host triple suitable for use in a cargo environment variable (uppercased).
 pub fn rustc_host_env() -> String {
     rustc_host().to_uppercase().replace('-', "_")
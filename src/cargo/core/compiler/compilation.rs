@@ -3,6 +3,7 @@ use std::env;
 use std::ffi::{OsStr, OsString};
 use std::path::PathBuf;
 
+use anyhow::Context as _;
 use cargo_platform::CfgExpr;
 use cargo_util::{paths, ProcessBuilder};
 
@@ -311,6 +312,14 @@ impl<'cfg> Compilation<'cfg> {
         }
 
         let metadata = pkg.manifest().metadata();
+        // Several license files are joined like `PATH`, with the platform's
+        // separator, since a path may contain `:` on Windows.
+        let license_file = env::join_paths(&metadata.license_file).with_context(|| {
+            format!(
+                "failed to join the license files of `{}` for CARGO_PKG_LICENSE_FILE",
+                pkg
+            )
+        })?;
 
         let cargo_exe = self.config.cargo_exe()?;
         cmd.env(crate::CARGO_ENV, cargo_exe);
@@ -342,14 +351,11 @@ impl<'cfg> Compilation<'cfg> {
                 "CARGO_PKG_LICENSE",
                 metadata.license.as_ref().unwrap_or(&String::new()),
             )
-            .env(
-                "CARGO_PKG_LICENSE_FILE",
-                metadata.license_file.as_ref().unwrap_or(&String::new()),
-            )
+            .env("CARGO_PKG_LICENSE_FILE", &license_file)
             .env("CARGO_PKG_AUTHORS", &pkg.authors().join(":"))
             .env(
                 "CARGO_PKG_RUST_VERSION",
-                &pkg.rust_version().unwrap_or(&String::new()),
+                &pkg.manifest().rust_version_minimum().unwrap_or_default(),
             )
             .cwd(pkg.root());
 
@@ -91,7 +91,8 @@
 //!    and summarize it similar to the other entries. Update the rest of the
 //!    documentation to add the new feature.
 
-use std::collections::BTreeSet;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashSet};
 use std::env;
 use std::fmt::{self, Write};
 use std::str::FromStr;
@@ -127,7 +128,7 @@ pub enum Edition {
 // - Update CLI_VALUES to include the new edition.
 // - Set LATEST_UNSTABLE to Some with the new edition.
 // - Add an unstable feature to the features! macro below for the new edition.
-// - Gate on that new feature in TomlManifest::to_real_manifest.
+// - Add the edition and that feature to `UNSTABLE_EDITIONS` in util/toml.
 // - Update the shell completion files.
 // - Update any failing tests (hopefully there are very few).
 // - Update unstable.md to add a new section for this new edition (see
@@ -139,6 +140,7 @@ pub enum Edition {
 // - Set LATEST_STABLE to the new version.
 // - Update `is_stable` to `true`.
 // - Set the editionNNNN feature to stable in the features macro below.
+// - Remove the edition from `UNSTABLE_EDITIONS` in util/toml.
 // - Update any tests that are affected.
 // - Update the man page for the --edition flag.
 // - Update unstable.md to move the edition section to the bottom.
@@ -287,6 +289,9 @@ macro_rules! features {
         pub struct Features {
             $($feature: bool,)*
             activated: Vec<String>,
+            /// Unstable features that `require` or `is_enabled` found enabled,
+            /// i.e. the `cargo-features` the manifest actually depends on.
+            used: RefCell<HashSet<&'static str>>,
             nightly_features_allowed: bool,
             is_local: bool,
         }
@@ -415,6 +420,39 @@ features! {
 
     // Allow specifying rustflags directly in a profile
     (unstable, workspace_inheritance, "", "reference/unstable.html#workspace-inheritance"),
+
+    // Inherit `rust-version` from the workspace when a member doesn't set it
+    (unstable, implicit_rust_version_inheritance, "", "reference/unstable.html#implicit-rust-version-inheritance"),
+
+    // Allow `rust-version` to be a version requirement like `">=1.70, <1.80"`
+    (unstable, rust_version_ranges, "", "reference/unstable.html#rust-version-ranges"),
+
+    // Warn about `rust-version` in a package that is never published
+    (unstable, lint_unpublished_rust_version, "", "reference/unstable.html#lint-unpublished-rust-version"),
+
+    // Warn about dependencies pinned to an exact `=` version
+    (unstable, lint_exact_version_pins, "", "reference/unstable.html#lint-exact-version-pins"),
+
+    // Inherit `version` from the workspace when a member doesn't set it
+    (unstable, implicit_version_inheritance, "", "reference/unstable.html#implicit-version-inheritance"),
+
+    // Warn about `overflow-checks = true` in profiles that inherit from `release`
+    (unstable, lint_release_overflow_checks, "", "reference/unstable.html#lint-release-overflow-checks"),
+
+    // Allow `build` to be an array of build scripts
+    (unstable, multiple_build_scripts, "", "reference/unstable.html#multiple-build-scripts"),
+
+    // Warn about `authors` entries that aren't a name with an optional `<email>`
+    (unstable, lint_author_emails, "", "reference/unstable.html#lint-author-emails"),
+
+    // Allow `license-file` to be an array of paths
+    (unstable, multiple_license_files, "", "reference/unstable.html#multiple-license-files"),
+
+    // Allow `publish` to be a table with a `reason` for not publishing
+    (unstable, publish_reason, "", "reference/unstable.html#publish-reason"),
+
+    // Warn about `homepage`, `documentation` and `repository` values that aren't absolute URLs
+    (unstable, lint_metadata_urls, "", "reference/unstable.html#lint-metadata-urls"),
 }
 
 pub struct Feature {
@@ -543,8 +581,25 @@ impl Features {
         &self.activated
     }
 
+    /// The names of the unstable features that were required while the
+    /// manifest was parsed, spelled as they appear in `cargo-features`.
+    pub fn used(&self) -> HashSet<String> {
+        self.used
+            .borrow()
+            .iter()
+            .map(|name| name.replace("_", "-"))
+            .collect()
+    }
+
+    fn mark_used(&self, feature: &Feature) {
+        if feature.stability != Status::Stable {
+            self.used.borrow_mut().insert(feature.name);
+        }
+    }
+
     pub fn require(&self, feature: &Feature) -> CargoResult<()> {
         if feature.is_enabled(self) {
+            self.mark_used(feature);
             return Ok(());
         }
         let feature_name = feature.name.replace("_", "-");
@@ -592,7 +647,11 @@ impl Features {
     }
 
     pub fn is_enabled(&self, feature: &Feature) -> bool {
-        feature.is_enabled(self)
+        let enabled = feature.is_enabled(self);
+        if enabled {
+            self.mark_used(feature);
+        }
+        enabled
     }
 }
 
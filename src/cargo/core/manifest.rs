@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
@@ -6,19 +6,20 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use anyhow::Context as _;
-use semver::Version;
+use semver::{Version, VersionReq};
 use serde::ser;
 use serde::Serialize;
 use toml_edit::easy as toml;
 use url::Url;
 
 use crate::core::compiler::{CompileKind, CrateType};
+use crate::core::dependency::DepKind;
 use crate::core::resolver::ResolveBehavior;
 use crate::core::{Dependency, PackageId, PackageIdSpec, SourceId, Summary};
 use crate::core::{Edition, Feature, Features, WorkspaceConfig};
 use crate::util::errors::*;
 use crate::util::interning::InternedString;
-use crate::util::toml::{TomlManifest, TomlProfiles};
+use crate::util::toml::{rust_version_lower_bound, TomlManifest, TomlProfiles};
 use crate::util::{short_hash, Config, Filesystem};
 
 pub enum EitherManifest {
@@ -52,16 +53,20 @@ pub struct Manifest {
     custom_metadata: Option<toml::Value>,
     profiles: Option<TomlProfiles>,
     publish: Option<Vec<String>>,
+    publish_reason: Option<String>,
     replace: Vec<(PackageIdSpec, Dependency)>,
     patch: HashMap<Url, Vec<Dependency>>,
     workspace: WorkspaceConfig,
     original: Rc<TomlManifest>,
     unstable_features: Features,
+    required_features_used: HashSet<String>,
     edition: Edition,
     rust_version: Option<String>,
+    rust_version_req: Option<VersionReq>,
     im_a_teapot: Option<bool>,
     default_run: Option<String>,
     metabuild: Option<Vec<String>>,
+    build_scripts: Vec<PathBuf>,
     resolve_behavior: Option<ResolveBehavior>,
 }
 
@@ -85,6 +90,7 @@ pub struct VirtualManifest {
     profiles: Option<TomlProfiles>,
     warnings: Warnings,
     features: Features,
+    required_features_used: HashSet<String>,
     resolve_behavior: Option<ResolveBehavior>,
 }
 
@@ -102,7 +108,7 @@ pub struct ManifestMetadata {
     pub keywords: Vec<String>,
     pub categories: Vec<String>,
     pub license: Option<String>,
-    pub license_file: Option<String>,
+    pub license_file: Vec<String>,
     pub description: Option<String>,   // Not in Markdown
     pub readme: Option<String>,        // File, not contents
     pub homepage: Option<String>,      // URL
@@ -112,6 +118,54 @@ pub struct ManifestMetadata {
     pub links: Option<String>,
 }
 
+impl ManifestMetadata {
+    /// Returns `documentation`, or the docs.rs page for `crate_name` if it is
+    /// unset, which is where crates.io links to in that case.
+    pub fn documentation_or_default(&self, crate_name: &str) -> Option<String> {
+        match &self.documentation {
+            Some(documentation) => Some(documentation.clone()),
+            None => Some(format!("https://docs.rs/{}", crate_name)),
+        }
+    }
+
+    /// Renders the resolved metadata as the `[package]` keys it came from,
+    /// in the order they are documented in the manifest format.
+    ///
+    /// Unset fields are left out. `badges` lives in its own top-level table
+    /// and is not included. `license-file` is only an array when several
+    /// files are listed, which needs the `multiple-license-files` feature.
+    pub fn to_toml(&self) -> toml_edit::Table {
+        fn string(table: &mut toml_edit::Table, key: &str, value: &Option<String>) {
+            if let Some(value) = value {
+                table.insert(key, toml_edit::value(value.as_str()));
+            }
+        }
+        fn array(table: &mut toml_edit::Table, key: &str, values: &[String]) {
+            if !values.is_empty() {
+                let values: toml_edit::Array = values.iter().map(String::as_str).collect();
+                table.insert(key, toml_edit::value(values));
+            }
+        }
+
+        let mut table = toml_edit::Table::new();
+        array(&mut table, "authors", &self.authors);
+        string(&mut table, "description", &self.description);
+        string(&mut table, "documentation", &self.documentation);
+        string(&mut table, "readme", &self.readme);
+        string(&mut table, "homepage", &self.homepage);
+        string(&mut table, "repository", &self.repository);
+        string(&mut table, "license", &self.license);
+        match &self.license_file[..] {
+            [file] => string(&mut table, "license-file", &Some(file.clone())),
+            files => array(&mut table, "license-file", files),
+        }
+        array(&mut table, "keywords", &self.keywords);
+        array(&mut table, "categories", &self.categories);
+        string(&mut table, "links", &self.links);
+        table
+    }
+}
+
 #[derive(Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TargetKind {
     Lib(Vec<CrateType>),
@@ -391,16 +445,19 @@ impl Manifest {
         custom_metadata: Option<toml::Value>,
         profiles: Option<TomlProfiles>,
         publish: Option<Vec<String>>,
+        publish_reason: Option<String>,
         replace: Vec<(PackageIdSpec, Dependency)>,
         patch: HashMap<Url, Vec<Dependency>>,
         workspace: WorkspaceConfig,
         unstable_features: Features,
         edition: Edition,
         rust_version: Option<String>,
+        rust_version_req: Option<VersionReq>,
         im_a_teapot: Option<bool>,
         default_run: Option<String>,
         original: Rc<TomlManifest>,
         metabuild: Option<Vec<String>>,
+        build_scripts: Vec<PathBuf>,
         resolve_behavior: Option<ResolveBehavior>,
     ) -> Manifest {
         Manifest {
@@ -416,16 +473,20 @@ impl Manifest {
             custom_metadata,
             profiles,
             publish,
+            publish_reason,
             replace,
             patch,
             workspace,
             unstable_features,
+            required_features_used: HashSet::new(),
             edition,
             rust_version,
+            rust_version_req,
             original,
             im_a_teapot,
             default_run,
             metabuild,
+            build_scripts,
             resolve_behavior,
         }
     }
@@ -433,6 +494,27 @@ impl Manifest {
     pub fn dependencies(&self) -> &[Dependency] {
         self.summary.dependencies()
     }
+    /// The distinct sources of all dependencies, including dev-, build- and
+    /// platform-specific ones.
+    pub fn dependency_sources(&self) -> BTreeSet<SourceId> {
+        self.dependencies().iter().map(|d| d.source_id()).collect()
+    }
+    /// The sorted names of the optional dependencies, as written in `Cargo.toml`.
+    ///
+    /// Both `[dependencies]` and `[target.*.dependencies]` are included, and
+    /// a renamed dependency is listed under its key rather than its
+    /// `package` name.
+    pub fn optional_dependencies(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self
+            .dependencies()
+            .iter()
+            .filter(|d| d.is_optional() && d.kind() == DepKind::Normal)
+            .map(|d| d.name_in_toml().as_str())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
     pub fn default_kind(&self) -> Option<CompileKind> {
         self.default_kind
     }
@@ -467,6 +549,19 @@ impl Manifest {
     pub fn targets_mut(&mut self) -> &mut [Target] {
         &mut self.targets
     }
+    /// Returns `true` if the package has a build script, whether from an
+    /// explicit `build` key, an implicit `build.rs`, or `metabuild`.
+    pub fn has_custom_build(&self) -> bool {
+        self.targets.iter().any(|t| t.is_custom_build())
+    }
+    /// Returns the absolute path of the package's build script, if it has
+    /// one. This is `None` for `metabuild`, which has no source file.
+    pub fn custom_build_path(&self) -> Option<&Path> {
+        self.targets
+            .iter()
+            .find(|t| t.is_custom_build())
+            .and_then(|t| t.src_path().path())
+    }
     pub fn version(&self) -> &Version {
         self.package_id().version()
     }
@@ -482,6 +577,10 @@ impl Manifest {
     pub fn publish(&self) -> &Option<Vec<String>> {
         &self.publish
     }
+    /// The `reason` given in `publish = { registry = false, reason = ".." }`.
+    pub fn publish_reason(&self) -> Option<&str> {
+        self.publish_reason.as_deref()
+    }
     pub fn replace(&self) -> &[(PackageIdSpec, Dependency)] {
         &self.replace
     }
@@ -491,6 +590,13 @@ impl Manifest {
     pub fn patch(&self) -> &HashMap<Url, Vec<Dependency>> {
         &self.patch
     }
+    /// The `(source URL, package name)` pairs patched by `[patch]`, sorted.
+    ///
+    /// The graph isn't known while the manifest is parsed, so it is up to the
+    /// caller to check these against the resolve and flag unused patches.
+    pub fn patched_names(&self) -> Vec<(Url, String)> {
+        patched_names(&self.patch)
+    }
     pub fn links(&self) -> Option<&str> {
         self.links.as_deref()
     }
@@ -504,6 +610,12 @@ impl Manifest {
         &self.unstable_features
     }
 
+    /// The subset of `cargo-features` that parsing this manifest actually
+    /// required. Anything else listed in `cargo-features` is unnecessary.
+    pub fn required_features_used(&self) -> &HashSet<String> {
+        &self.required_features_used
+    }
+
     /// The style of resolver behavior to use, declared with the `resolver` field.
     ///
     /// Returns `None` if it is not specified.
@@ -518,7 +630,7 @@ impl Manifest {
         }
     }
 
-    pub fn feature_gate(&self) -> CargoResult<()> {
+    pub fn feature_gate(&mut self) -> CargoResult<()> {
         if self.im_a_teapot.is_some() {
             self.unstable_features
                 .require(Feature::test_dummy_unstable())
@@ -537,6 +649,9 @@ impl Manifest {
                 })?;
         }
 
+        // This is the last feature check made while loading the manifest.
+        self.required_features_used = self.unstable_features.used();
+
         Ok(())
     }
 
@@ -557,6 +672,26 @@ impl Manifest {
         self.rust_version.as_deref()
     }
 
+    /// The `rust-version` as a single version, for places that can't take a
+    /// requirement: a range like `>=1.70, <1.80` becomes the lowest version
+    /// it allows.
+    pub fn rust_version_minimum(&self) -> Option<String> {
+        let rust_version = self.rust_version.as_deref()?;
+        if rust_version.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            return Some(rust_version.to_string());
+        }
+        let req = self.rust_version_req.as_ref()?;
+        Some(rust_version_lower_bound(req).to_string())
+    }
+
+    /// Whether the declared `rust-version` permits building with the given
+    /// Rust version, or `None` if the package doesn't declare one.
+    pub fn rust_version_allows(&self, version: &Version) -> Option<bool> {
+        self.rust_version_req
+            .as_ref()
+            .map(|req| req.matches(version))
+    }
+
     pub fn custom_metadata(&self) -> Option<&toml::Value> {
         self.custom_metadata.as_ref()
     }
@@ -569,6 +704,12 @@ impl Manifest {
         self.metabuild.as_ref()
     }
 
+    /// The absolute paths of every build script named by `package.build`,
+    /// or of the implicit `build.rs`, in the order they are declared.
+    pub fn build_scripts(&self) -> &[PathBuf] {
+        &self.build_scripts
+    }
+
     pub fn metabuild_path(&self, target_dir: Filesystem) -> PathBuf {
         let hash = short_hash(&self.package_id());
         target_dir
@@ -593,6 +734,7 @@ impl VirtualManifest {
             workspace,
             profiles,
             warnings: Warnings::new(),
+            required_features_used: features.used(),
             features,
             resolve_behavior,
         }
@@ -606,6 +748,11 @@ impl VirtualManifest {
         &self.patch
     }
 
+    /// See [`Manifest::patched_names`].
+    pub fn patched_names(&self) -> Vec<(Url, String)> {
+        patched_names(&self.patch)
+    }
+
     pub fn workspace_config(&self) -> &WorkspaceConfig {
         &self.workspace
     }
@@ -626,6 +773,12 @@ impl VirtualManifest {
         &self.features
     }
 
+    /// The subset of `cargo-features` that parsing this manifest actually
+    /// required.
+    pub fn required_features_used(&self) -> &HashSet<String> {
+        &self.required_features_used
+    }
+
     /// The style of resolver behavior to use, declared with the `resolver` field.
     ///
     /// Returns `None` if it is not specified.
@@ -634,6 +787,18 @@ impl VirtualManifest {
     }
 }
 
+fn patched_names(patch: &HashMap<Url, Vec<Dependency>>) -> Vec<(Url, String)> {
+    let mut names: Vec<_> = patch
+        .iter()
+        .flat_map(|(url, deps)| {
+            deps.iter()
+                .map(move |dep| (url.clone(), dep.package_name().to_string()))
+        })
+        .collect();
+    names.sort_unstable();
+    names
+}
+
 impl Target {
     fn new(src_path: TargetSourcePath, edition: Edition) -> Target {
         Target {
@@ -996,4 +1161,8 @@ impl Warnings {
     pub fn warnings(&self) -> &[DelayedWarning] {
         &self.0
     }
+
+    pub fn warnings_mut(&mut self) -> &mut [DelayedWarning] {
+        &mut self.0
+    }
 }
@@ -82,7 +82,7 @@ pub struct SerializedPackage {
     version: Version,
     id: PackageId,
     license: Option<String>,
-    license_file: Option<String>,
+    license_file: Option<SerializedLicenseFile>,
     description: Option<String>,
     source: SourceId,
     dependencies: Vec<Dependency>,
@@ -106,6 +106,15 @@ pub struct SerializedPackage {
     rust_version: Option<String>,
 }
 
+/// A single license file is serialized as a string, as it was before multiple
+/// license files were supported, and several as a list.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum SerializedLicenseFile {
+    One(String),
+    Many(Vec<String>),
+}
+
 impl Package {
     /// Creates a package from a manifest and its location.
     pub fn new(manifest: Manifest, manifest_path: &Path) -> Package {
@@ -196,11 +205,10 @@ impl Package {
     }
 
     pub fn to_registry_toml(&self, ws: &Workspace<'_>) -> CargoResult<String> {
-        let manifest = self
+        let toml = self
             .manifest()
             .original()
-            .prepare_for_publish(ws, self.root())?;
-        let toml = toml::to_string_pretty(&manifest)?;
+            .to_published_toml_string(ws, self.root())?;
         Ok(format!("{}\n{}", MANIFEST_PREAMBLE, toml))
     }
 
@@ -242,7 +250,11 @@ impl Package {
             version: package_id.version().clone(),
             id: package_id,
             license: manmeta.license.clone(),
-            license_file: manmeta.license_file.clone(),
+            license_file: match manmeta.license_file.as_slice() {
+                [] => None,
+                [file] => Some(SerializedLicenseFile::One(file.clone())),
+                files => Some(SerializedLicenseFile::Many(files.to_vec())),
+            },
             description: manmeta.description.clone(),
             source: summary.source_id(),
             dependencies: summary.dependencies().to_vec(),
@@ -3,7 +3,7 @@ use crate::core::dependency::Artifact;
 use crate::core::resolver::features::FeaturesFor;
 use crate::core::{PackageId, PackageIdSpec, Resolve, Shell, Target, Workspace};
 use crate::util::interning::InternedString;
-use crate::util::toml::{ProfilePackageSpec, StringOrBool, TomlProfile, TomlProfiles, U32OrBool};
+use crate::util::toml::{ProfilePackageSpec, StringOrBool, TomlProfile, TomlProfiles};
 use crate::util::{closest_msg, config, CargoResult, Config};
 use anyhow::{bail, Context as _};
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -199,10 +199,15 @@ impl Profiles {
 
                 match profiles.get(&inherits_name) {
                     None => {
+                        let defined = ["dev", "release"]
+                            .into_iter()
+                            .chain(profiles.keys().map(|p| p.as_str()))
+                            .filter(|p| *p != name.as_str());
                         bail!(
-                            "profile `{}` inherits from `{}`, but that profile is not defined",
+                            "profile `{}` inherits from `{}`, but that profile is not defined{}",
                             name,
-                            inherits_name
+                            inherits_name,
+                            closest_msg(&inherits_name, defined, |p| *p)
                         );
                     }
                     Some(parent) => self.process_chain(inherits_name, parent, set, profiles)?,
@@ -486,11 +491,8 @@ fn merge_profile(profile: &mut Profile, toml: &TomlProfile) {
     if toml.codegen_units.is_some() {
         profile.codegen_units = toml.codegen_units;
     }
-    match toml.debug {
-        Some(U32OrBool::U32(debug)) => profile.debuginfo = Some(debug),
-        Some(U32OrBool::Bool(true)) => profile.debuginfo = Some(2),
-        Some(U32OrBool::Bool(false)) => profile.debuginfo = None,
-        None => {}
+    if let Some(debug) = &toml.debug {
+        profile.debuginfo = debug.level();
     }
     if let Some(debug_assertions) = toml.debug_assertions {
         profile.debug_assertions = debug_assertions;
@@ -1608,6 +1608,24 @@ impl WorkspaceRootConfig {
         self.members.is_some()
     }
 
+    /// Returns the `members` glob patterns that don't match any directory.
+    ///
+    /// Plain paths without glob characters are left out, since a missing
+    /// member directory is already reported as an error when it's loaded.
+    pub fn unmatched_member_globs(&self) -> CargoResult<Vec<&str>> {
+        let mut unmatched = Vec::new();
+        for glob in self.members.iter().flatten() {
+            if !glob.contains(&['*', '?', '['][..]) {
+                continue;
+            }
+            let expanded = Self::expand_member_path(&self.root_dir.join(glob))?;
+            if !expanded.iter().any(|path| path.is_dir()) {
+                unmatched.push(glob.as_str());
+            }
+        }
+        Ok(unmatched)
+    }
+
     fn members_paths(&self, globs: &[String]) -> CargoResult<Vec<PathBuf>> {
         let mut expanded_list = Vec::new();
 
@@ -1660,7 +1678,9 @@ pub fn resolve_relative_path(
     rel_path: &str,
 ) -> CargoResult<String> {
     let joined_path = normalize_path(&old_root.join(rel_path));
-    match diff_paths(joined_path, new_root) {
+    // `new_root` isn't necessarily normalized, and `diff_paths` can't see
+    // past `..` components.
+    match diff_paths(joined_path, normalize_path(new_root)) {
         None => Err(anyhow!(
             "`{}` was defined in {} but could not be resolved with {}",
             label,
@@ -651,21 +651,22 @@ pub fn create_bcx<'a, 'cfg>(
         );
 
         for unit in unit_graph.keys() {
-            let version = match unit.pkg.rust_version() {
-                Some(v) => v,
-                None => continue,
-            };
-
-            let req = semver::VersionReq::parse(version).unwrap();
-            if req.matches(&untagged_version) {
+            if unit.pkg.manifest().rust_version_allows(&untagged_version) != Some(false) {
                 continue;
             }
+            let version = unit.pkg.rust_version().unwrap();
+            // A requirement like `>=1.70, <1.80` can't be read as "or newer".
+            let requirement = if version.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                format!("{} or newer", version)
+            } else {
+                format!("`{}`", version)
+            };
 
             anyhow::bail!(
-                "package `{}` cannot be built because it requires rustc {} or newer, \
+                "package `{}` cannot be built because it requires rustc {}, \
                  while the currently active rustc version is {}",
                 unit.pkg,
-                version,
+                requirement,
                 current_version,
             );
         }
@@ -19,6 +19,7 @@ use anyhow::Context as _;
 use cargo_util::paths;
 use flate2::read::GzDecoder;
 use flate2::{Compression, GzBuilder};
+use ignore::gitignore::GitignoreBuilder;
 use log::debug;
 use serde::Serialize;
 use tar::{Archive, Builder, EntryType, Header, HeaderMode};
@@ -90,12 +91,7 @@ pub fn package_one(
         check_metadata(pkg, config)?;
     }
 
-    if !pkg.manifest().exclude().is_empty() && !pkg.manifest().include().is_empty() {
-        config.shell().warn(
-            "both package.include and package.exclude are specified; \
-             the exclude list will be ignored",
-        )?;
-    }
+    check_excluded_path_deps(pkg, config)?;
     let src_files = src.list_files(pkg)?;
 
     // Check (git) repository state, getting the current commit hash if not
@@ -121,6 +117,7 @@ pub fn package_one(
     for dep in pkg.dependencies() {
         super::check_dep_has_version(dep, false)?;
     }
+    check_rust_version_is_single(pkg)?;
 
     let filename = format!("{}-{}.crate", pkg.name(), pkg.version());
     let dir = ws.target_dir().join("package");
@@ -258,9 +255,25 @@ fn build_ar_list(
             contents: FileContents::Generated(GeneratedFile::VcsInfo(vcs_info)),
         });
     }
-    if let Some(license_file) = &pkg.manifest().metadata().license_file {
+    // License files outside of the package are copied into its root, so two
+    // of them with the same file name would overwrite each other.
+    let mut copied_license_files = HashMap::new();
+    for license_file in &pkg.manifest().metadata().license_file {
         let license_path = Path::new(license_file);
         let abs_file_path = paths::normalize_path(&pkg.root().join(license_path));
+        if abs_file_path.strip_prefix(pkg.root()).is_err() {
+            if let Some(file_name) = license_path.file_name() {
+                if let Some(other) = copied_license_files.insert(file_name, license_file) {
+                    anyhow::bail!(
+                        "license-file `{}` and `{}` are both outside of the package \
+                         and would both be copied to `{}` in its root",
+                        other,
+                        license_file,
+                        file_name.to_string_lossy()
+                    );
+                }
+            }
+        }
         if abs_file_path.exists() {
             check_for_file_and_add(
                 "license-file",
@@ -389,7 +402,7 @@ fn check_metadata(pkg: &Package, config: &Config) -> CargoResult<()> {
     macro_rules! lacking {
         ($( $($field: ident)||* ),*) => {{
             $(
-                if $(md.$field.as_ref().map_or(true, |s| s.is_empty()))&&* {
+                if $(md.$field.iter().all(|s| s.is_empty()))&&* {
                     $(missing.push(stringify!($field).replace("_", "-"));)*
                 }
             )*
@@ -420,6 +433,69 @@ fn check_metadata(pkg: &Package, config: &Config) -> CargoResult<()> {
     Ok(())
 }
 
+/// Checks that `rust-version` is a single version rather than a range, as
+/// registries and older versions of Cargo only understand the former.
+fn check_rust_version_is_single(pkg: &Package) -> CargoResult<()> {
+    let manifest = pkg.manifest();
+    if let (Some(rust_version), Some(minimum)) =
+        (manifest.rust_version(), manifest.rust_version_minimum())
+    {
+        if rust_version != minimum {
+            anyhow::bail!(
+                "`rust-version` of \"{}\" is a version requirement, which can't be published\n\
+                 Set it to the lowest supported version, \"{}\", before publishing.",
+                rust_version,
+                minimum
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Warns about path dependencies inside the package that `package.exclude`
+/// leaves out of the `.crate` file, which makes packaging fail confusingly.
+fn check_excluded_path_deps(pkg: &Package, config: &Config) -> CargoResult<()> {
+    let manifest = pkg.manifest();
+    if manifest.exclude().is_empty() || !manifest.include().is_empty() {
+        return Ok(());
+    }
+    // Compiled the same way as when listing the package's files.
+    let mut builder = GitignoreBuilder::new(pkg.root());
+    for rule in manifest.exclude() {
+        builder.add_line(None, rule)?;
+    }
+    let exclude = builder.build()?;
+
+    let mut excluded = BTreeSet::new();
+    for dep in pkg.dependencies() {
+        if !dep.source_id().is_path() {
+            continue;
+        }
+        let path = match dep.source_id().url().to_file_path() {
+            Ok(path) => path,
+            Err(_) => continue,
+        };
+        let relative = match path.strip_prefix(pkg.root()) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative.to_path_buf(),
+            _ => continue,
+        };
+        if exclude
+            .matched_path_or_any_parents(&relative, true)
+            .is_ignore()
+        {
+            excluded.insert(relative);
+        }
+    }
+    for relative in excluded {
+        config.shell().warn(format!(
+            "path dependency `{}` is inside a directory excluded by \
+             `package.exclude`, so it is not included when packaging",
+            relative.display()
+        ))?;
+    }
+    Ok(())
+}
+
 /// Checks if the package source is in a *git* DVCS repository. If *git*, and
 /// the source is *dirty* (e.g., has uncommitted changes) then `bail!` with an
 /// informative message. Otherwise return the sha1 hash of the current *HEAD*
@@ -133,11 +133,16 @@ pub fn publish(ws: &Workspace<'_>, opts: &PublishOpts<'_>) -> CargoResult<()> {
             .clone()
             .unwrap_or_else(|| CRATES_IO_REGISTRY.to_string());
         if !allowed_registries.contains(&reg_name) {
+            let reason = match pkg.manifest().publish_reason() {
+                Some(reason) => format!("\nReason: {}", reason),
+                None => String::new(),
+            };
             bail!(
                 "`{}` cannot be published.\n\
-                 The registry `{}` is not listed in the `publish` value in Cargo.toml.",
+                 The registry `{}` is not listed in the `publish` value in Cargo.toml.{}",
                 pkg.name(),
-                reg_name
+                reg_name,
+                reason
             );
         }
     }
@@ -296,11 +301,14 @@ fn transmit(
                 .with_context(|| format!("failed to read `readme` file for package `{}`", pkg))
         })
         .transpose()?;
-    if let Some(ref file) = *license_file {
+    for file in license_file {
         if !pkg.root().join(file).exists() {
             bail!("the license file `{}` does not exist", file)
         }
     }
+    // The registry API only has room for one license file, the others are
+    // still listed in the published manifest.
+    let license_file = license_file.first().cloned();
 
     // Do not upload if performing a dry run
     if dry_run {
@@ -338,7 +346,7 @@ fn transmit(
                 readme_file: readme.clone(),
                 repository: repository.clone(),
                 license: license.clone(),
-                license_file: license_file.clone(),
+                license_file,
                 badges: badges.clone(),
                 links: links.clone(),
             },
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::marker::PhantomData;
@@ -8,6 +9,7 @@ use std::str;
 use anyhow::{anyhow, bail, Context as _};
 use cargo_platform::Platform;
 use cargo_util::paths;
+use ignore::gitignore::GitignoreBuilder;
 use lazycell::LazyCell;
 use log::{debug, trace};
 use semver::{self, VersionReq};
@@ -18,11 +20,12 @@ use toml_edit::easy as toml;
 use url::Url;
 
 use crate::core::compiler::{CompileKind, CompileTarget};
-use crate::core::dependency::{Artifact, ArtifactTarget, DepKind};
+use crate::core::dependency::{Artifact, ArtifactKind, ArtifactTarget, DepKind};
 use crate::core::manifest::{ManifestMetadata, TargetSourcePath, Warnings};
 use crate::core::resolver::ResolveBehavior;
 use crate::core::{
     find_workspace_root, resolve_relative_path, Dependency, Manifest, PackageId, Summary, Target,
+    TargetKind,
 };
 use crate::core::{Edition, EitherManifest, Feature, Features, VirtualManifest, Workspace};
 use crate::core::{GitReference, PackageIdSpec, SourceId, WorkspaceConfig, WorkspaceRootConfig};
@@ -57,13 +60,98 @@ pub fn read_manifest(
         path.display(),
         source_id
     );
-    let contents = paths::read(path).map_err(|err| ManifestError::new(err, path.into()))?;
+    let contents = paths::read_bytes(path).map_err(|err| ManifestError::new(err, path.into()))?;
 
-    read_manifest_from_str(&contents, path, source_id, config)
+    read_manifest_from_bytes(&contents, path, source_id, config)
         .with_context(|| format!("failed to parse manifest at `{}`", path.display()))
         .map_err(|err| ManifestError::new(err, path.into()))
 }
 
+/// Follows the path dependencies of the package at `root` transitively and
+/// returns the directory of every package reached, in the order they were
+/// first found.
+///
+/// Unlike walking the nested paths of [`read_manifest`] naively, this bails
+/// with the offending chain of directories if the path dependencies form a
+/// cycle. Every kind of dependency is followed, so a cycle through a
+/// dev-dependency is reported as well.
+pub fn collect_path_deps_acyclic(root: &Path, config: &Config) -> CargoResult<Vec<PathBuf>> {
+    fn visit(
+        dir: PathBuf,
+        config: &Config,
+        stack: &mut Vec<PathBuf>,
+        found: &mut Vec<PathBuf>,
+    ) -> CargoResult<()> {
+        if let Some(start) = stack.iter().position(|p| *p == dir) {
+            let cycle = stack[start..]
+                .iter()
+                .chain(Some(&dir))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            bail!("cyclic path dependencies found: {}", cycle);
+        }
+        if found.contains(&dir) {
+            return Ok(());
+        }
+        found.push(dir.clone());
+        let source_id = SourceId::for_path(&dir)?;
+        let (_, nested_paths) = read_manifest(&dir.join("Cargo.toml"), source_id, config)?;
+        stack.push(dir.clone());
+        for nested in nested_paths {
+            visit(
+                paths::normalize_path(&dir.join(nested)),
+                config,
+                stack,
+                found,
+            )?;
+        }
+        stack.pop();
+        Ok(())
+    }
+
+    let mut found = Vec::new();
+    visit(
+        paths::normalize_path(&config.cwd().join(root)),
+        config,
+        &mut Vec::new(),
+        &mut found,
+    )?;
+    // The first directory found is `root` itself.
+    found.remove(0);
+    Ok(found)
+}
+
+/// Parse the raw bytes of a `Cargo.toml` as a Cargo manifest.
+///
+/// A leading UTF-8 byte order mark is ignored and `\r\n` line endings are
+/// normalized to `\n`, so manifests saved by Windows editors are read the
+/// same as any other. See [`read_manifest_from_str`] for what is returned.
+pub fn read_manifest_from_bytes(
+    contents: &[u8],
+    manifest_file: &Path,
+    source_id: SourceId,
+    config: &Config,
+) -> CargoResult<(EitherManifest, Vec<PathBuf>)> {
+    let contents = decode_manifest(contents)?;
+    read_manifest_from_str(&contents, manifest_file, source_id, config)
+}
+
+fn decode_manifest(contents: &[u8]) -> CargoResult<Cow<'_, str>> {
+    let contents = str::from_utf8(contents).map_err(|e| {
+        anyhow!(
+            "manifest is not valid UTF-8: invalid byte sequence at byte offset {}",
+            e.valid_up_to()
+        )
+    })?;
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+    if contents.contains("\r\n") {
+        Ok(Cow::Owned(contents.replace("\r\n", "\n")))
+    } else {
+        Ok(Cow::Borrowed(contents))
+    }
+}
+
 /// Parse an already-loaded `Cargo.toml` as a Cargo manifest.
 ///
 /// This could result in a real or virtual manifest being returned.
@@ -77,15 +165,147 @@ pub fn read_manifest_from_str(
     manifest_file: &Path,
     source_id: SourceId,
     config: &Config,
+) -> CargoResult<(EitherManifest, Vec<PathBuf>)> {
+    read_manifest_from_str_limited(
+        contents,
+        manifest_file,
+        source_id,
+        config,
+        ManifestLimits::default(),
+    )
+}
+
+/// Limits on the shape of a manifest, used to reject untrusted manifests
+/// before doing the expensive work of deserializing them.
+///
+/// The default imposes no limits.
+#[derive(Clone, Copy, Debug)]
+pub struct ManifestLimits {
+    /// Maximum size of the manifest, in bytes.
+    pub max_bytes: usize,
+    /// Maximum nesting depth of tables and arrays.
+    pub max_depth: usize,
+    /// Maximum number of dependencies, summed over all dependency tables.
+    pub max_dependencies: usize,
+}
+
+impl Default for ManifestLimits {
+    fn default() -> Self {
+        ManifestLimits {
+            max_bytes: usize::MAX,
+            max_depth: usize::MAX,
+            max_dependencies: usize::MAX,
+        }
+    }
+}
+
+impl ManifestLimits {
+    /// Checks the size of the raw manifest contents.
+    pub fn check_size(&self, contents: &str) -> CargoResult<()> {
+        if contents.len() > self.max_bytes {
+            bail!(
+                "manifest is {} bytes, which exceeds the limit of {} bytes",
+                contents.len(),
+                self.max_bytes
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks the nesting depth and number of dependencies of a parsed
+    /// manifest.
+    pub fn check_document(&self, document: &toml_edit::Document) -> CargoResult<()> {
+        let depth = table_depth(document.as_table());
+        if depth > self.max_depth {
+            bail!(
+                "manifest has tables or arrays nested {} levels deep, \
+                 which exceeds the limit of {}",
+                depth,
+                self.max_depth
+            );
+        }
+
+        const DEP_TABLES: [&str; 5] = [
+            "dependencies",
+            "dev-dependencies",
+            "dev_dependencies",
+            "build-dependencies",
+            "build_dependencies",
+        ];
+        let count_deps = |table: &dyn toml_edit::TableLike| -> usize {
+            DEP_TABLES
+                .iter()
+                .filter_map(|key| table.get(key).and_then(|deps| deps.as_table_like()))
+                .map(|deps| deps.len())
+                .sum()
+        };
+        let mut dependencies = count_deps(document.as_table());
+        if let Some(platforms) = document.get("target").and_then(|t| t.as_table_like()) {
+            for (_, platform) in platforms.iter() {
+                if let Some(platform) = platform.as_table_like() {
+                    dependencies += count_deps(platform);
+                }
+            }
+        }
+        if dependencies > self.max_dependencies {
+            bail!(
+                "manifest declares {} dependencies, which exceeds the limit of {}",
+                dependencies,
+                self.max_dependencies
+            );
+        }
+        Ok(())
+    }
+}
+
+fn table_depth(table: &toml_edit::Table) -> usize {
+    fn item_depth(item: &toml_edit::Item) -> usize {
+        match item {
+            toml_edit::Item::None => 0,
+            toml_edit::Item::Value(value) => value_depth(value),
+            toml_edit::Item::Table(table) => table_depth(table),
+            toml_edit::Item::ArrayOfTables(array) => {
+                1 + array.iter().map(table_depth).max().unwrap_or(0)
+            }
+        }
+    }
+
+    fn value_depth(value: &toml_edit::Value) -> usize {
+        match value {
+            toml_edit::Value::Array(array) => 1 + array.iter().map(value_depth).max().unwrap_or(0),
+            toml_edit::Value::InlineTable(table) => {
+                1 + table.iter().map(|(_, v)| value_depth(v)).max().unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+
+    1 + table
+        .iter()
+        .map(|(_, item)| item_depth(item))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Like [`read_manifest_from_str`], but first rejects manifests that exceed
+/// the given [`ManifestLimits`].
+pub fn read_manifest_from_str_limited(
+    contents: &str,
+    manifest_file: &Path,
+    source_id: SourceId,
+    config: &Config,
+    limits: ManifestLimits,
 ) -> CargoResult<(EitherManifest, Vec<PathBuf>)> {
     let package_root = manifest_file.parent().unwrap();
 
+    limits.check_size(contents)?;
     let toml = {
         let pretty_filename = manifest_file
             .strip_prefix(config.cwd())
             .unwrap_or(manifest_file);
         parse_document(contents, pretty_filename, config)?
     };
+    limits.check_document(&toml)?;
 
     // Provide a helpful error message for a common user error.
     if let Some(package) = toml.get("package").or_else(|| toml.get("project")) {
@@ -111,6 +331,18 @@ pub fn read_manifest_from_str(
     })?;
     let add_unused = |warnings: &mut Warnings| {
         for key in unused {
+            // Point out misspellings of the fields that can be inherited.
+            if let Some(field) = key.strip_prefix("workspace.package.") {
+                let suggestion = util::closest(field, INHERITABLE_FIELDS.iter(), |f| f)
+                    .filter(|_| !field.contains('.'));
+                if let Some(suggestion) = suggestion {
+                    warnings.add_warning(format!(
+                        "unused manifest key: {}, did you mean `workspace.package.{}`?",
+                        key, suggestion
+                    ));
+                    continue;
+                }
+            }
             warnings.add_warning(format!("unused manifest key: {}", key));
             if key == "profiles.debug" {
                 warnings.add_warning("use `[profile.dev]` to configure debug builds".to_string());
@@ -142,8 +374,11 @@ pub fn read_manifest_from_str(
         }
     }
     return if manifest.project.is_some() || manifest.package.is_some() {
+        let me = &manifest;
         let (mut manifest, paths) =
-            TomlManifest::to_real_manifest(&manifest, source_id, package_root, config)?;
+            TomlManifest::to_real_manifest(me, source_id, package_root, config)?;
+        let package_name = manifest.name();
+        point_at_deprecated_tables(me, &package_name, contents, manifest.warnings_mut());
         add_unused(manifest.warnings_mut());
         if manifest.targets().iter().all(|t| t.is_custom_build()) {
             bail!(
@@ -193,6 +428,11 @@ pub fn read_manifest_from_str(
 /// accepted and display a warning to the user in that case. The `file` and `config`
 /// parameters are only used by this fallback path.
 pub fn parse(toml: &str, _file: &Path, _config: &Config) -> CargoResult<toml::Value> {
+    parse_str(toml)
+}
+
+/// Same as [`parse`], for callers that don't have a file path or a [`Config`].
+pub fn parse_str(toml: &str) -> CargoResult<toml::Value> {
     // At the moment, no compatibility checks are needed.
     toml.parse()
         .map_err(|e| anyhow::Error::from(e).context("could not parse input as TOML"))
@@ -203,18 +443,265 @@ pub fn parse_document(
     _file: &Path,
     _config: &Config,
 ) -> CargoResult<toml_edit::Document> {
+    parse_document_str(toml)
+}
+
+/// Same as [`parse_document`], for callers that don't have a file path or a
+/// [`Config`].
+pub fn parse_document_str(toml: &str) -> CargoResult<toml_edit::Document> {
     // At the moment, no compatibility checks are needed.
     toml.parse()
         .map_err(|e| anyhow::Error::from(e).context("could not parse input as TOML"))
 }
 
+/// Parses just the `[dependencies]` table of a TOML document, without
+/// requiring the rest of a manifest.
+pub fn parse_dependencies(toml: &str) -> CargoResult<BTreeMap<String, TomlDependency>> {
+    let mut value = parse_str(toml)?;
+    let dependencies = value
+        .as_table_mut()
+        .and_then(|table| table.remove("dependencies"))
+        .ok_or_else(|| anyhow!("no `[dependencies]` table found"))?;
+    dependencies
+        .try_into()
+        .context("failed to parse the `[dependencies]` table")
+}
+
+/// Reads `members`, `default-members` and `exclude`, in that order, from the
+/// `[workspace]` table of a TOML document.
+///
+/// Returns `None` if there is no `[workspace]` table. Keys that aren't set
+/// are returned as empty lists.
+pub fn read_workspace_members(
+    contents: &str,
+) -> CargoResult<Option<(Vec<String>, Vec<String>, Vec<String>)>> {
+    Ok(parse_workspace(contents)?.map(|workspace| {
+        (
+            workspace.members().to_vec(),
+            workspace.default_members().to_vec(),
+            workspace.exclude().to_vec(),
+        )
+    }))
+}
+
+/// Reads the resolver behavior selected by `workspace.resolver` in a TOML
+/// document.
+///
+/// Returns `None` if there is no `[workspace]` table or it doesn't set
+/// `resolver`. Unlike a virtual manifest, this doesn't fall back to a
+/// default, so callers can tell an explicit `resolver = "1"` apart.
+pub fn workspace_resolver(contents: &str) -> CargoResult<Option<ResolveBehavior>> {
+    match parse_workspace(contents)? {
+        Some(workspace) => workspace.resolve_behavior(),
+        None => Ok(None),
+    }
+}
+
+/// Parses only the `[workspace]` table of a TOML document, if there is one.
+fn parse_workspace(contents: &str) -> CargoResult<Option<TomlWorkspace>> {
+    let mut value = parse_str(contents)?;
+    let workspace = match value
+        .as_table_mut()
+        .and_then(|table| table.remove("workspace"))
+    {
+        Some(workspace) => workspace,
+        None => return Ok(None),
+    };
+    let workspace = workspace
+        .try_into()
+        .context("failed to parse the `[workspace]` table")?;
+    Ok(Some(workspace))
+}
+
 /// Warn about paths that have been deprecated and may conflict.
 fn warn_on_deprecated(new_path: &str, name: &str, kind: &str, warnings: &mut Vec<String>) {
+    warnings.push(deprecated_message(new_path, name, kind))
+}
+
+fn deprecated_message(new_path: &str, name: &str, kind: &str) -> String {
     let old_path = new_path.replace("-", "_");
-    warnings.push(format!(
+    format!(
         "conflicting between `{new_path}` and `{old_path}` in the `{name}` {kind}.\n
         `{old_path}` is ignored and not recommended for use in the future"
-    ))
+    )
+}
+
+/// Adds the line of `contents` where the ignored table is declared to the
+/// warnings [`warn_on_deprecated`] gave for `me`.
+fn point_at_deprecated_tables(
+    me: &TomlManifest,
+    package_name: &str,
+    contents: &str,
+    warnings: &mut Warnings,
+) {
+    let mut tables = Vec::new();
+    if me.dev_dependencies.is_some() && me.dev_dependencies2.is_some() {
+        let message = deprecated_message("dev-dependencies", package_name, "package");
+        tables.push((message, vec!["dev_dependencies"]));
+    }
+    if me.build_dependencies.is_some() && me.build_dependencies2.is_some() {
+        let message = deprecated_message("build-dependencies", package_name, "package");
+        tables.push((message, vec!["build_dependencies"]));
+    }
+    for (name, platform) in me.target.iter().flatten() {
+        if platform.build_dependencies.is_some() && platform.build_dependencies2.is_some() {
+            let message = deprecated_message("build-dependencies", name, "platform target");
+            tables.push((message, vec!["target", name, "build_dependencies"]));
+        }
+        if platform.dev_dependencies.is_some() && platform.dev_dependencies2.is_some() {
+            let message = deprecated_message("dev-dependencies", name, "platform target");
+            tables.push((message, vec!["target", name, "dev_dependencies"]));
+        }
+    }
+    if tables.is_empty() {
+        return;
+    }
+
+    let document = match contents.parse::<toml_edit::Document>() {
+        Ok(document) => document,
+        Err(_) => return,
+    };
+    for (message, path) in tables {
+        let line = match key_line(&document, &path) {
+            Some(line) => line,
+            None => continue,
+        };
+        for warning in warnings.warnings_mut() {
+            if warning.message == message {
+                warning.message.push_str(&format!(
+                    "\n        `{}` is declared on line {} of Cargo.toml",
+                    path.last().unwrap(),
+                    line
+                ));
+            }
+        }
+    }
+}
+
+/// Returns the 1-based line of `document` where the key at `path` is
+/// declared.
+///
+/// `toml_edit` doesn't track spans, but it renders a parsed document back to
+/// its exact source, so a marker is put in front of the key and looked up in
+/// the rendered text.
+fn key_line(document: &toml_edit::Document, path: &[&str]) -> Option<usize> {
+    // Can't appear in a parsed document, so it can't be confused with its
+    // contents.
+    const MARKER: char = '\u{0}';
+
+    fn mark(table: &mut dyn toml_edit::TableLike, key: &str, rest: &[&str]) -> Option<()> {
+        let item = table.get_mut(key)?;
+        if let Some((next, rest)) = rest.split_first() {
+            return mark(item.as_table_like_mut()?, next, rest);
+        }
+        let decor = match item {
+            // A table with its own header; the marker goes before the `[`.
+            toml_edit::Item::Table(table) if !table.is_implicit() && !table.is_dotted() => {
+                table.decor_mut()
+            }
+            // A table that only exists through its sub-tables or dotted
+            // keys is declared where its first entry is.
+            toml_edit::Item::Table(table) => {
+                let first = table.iter().next()?.0.to_string();
+                return mark(table, &first, &[]);
+            }
+            _ => table.key_decor_mut(key)?,
+        };
+        let prefix = format!("{}{}", decor.prefix().unwrap_or(""), MARKER);
+        decor.set_prefix(prefix);
+        Some(())
+    }
+
+    let mut document = document.clone();
+    let (first, rest) = path.split_first()?;
+    mark(document.as_table_mut(), first, rest)?;
+    let rendered = document.to_string();
+    let at = rendered.find(MARKER)?;
+    Some(rendered[..at].matches('\n').count() + 1)
+}
+
+/// Moves the value of a deprecated `snake_case` alias into its canonical
+/// `kebab-case` field, warning about the collapse.
+///
+/// If both are set, the canonical value wins, matching how the manifest is
+/// interpreted when it's parsed.
+fn collapse_alias<T>(
+    canonical: &mut Option<T>,
+    alias: &mut Option<T>,
+    new_path: &str,
+    name: &str,
+    kind: &str,
+    warnings: &mut Vec<String>,
+) {
+    if let Some(value) = alias.take() {
+        if canonical.is_some() {
+            warn_on_deprecated(new_path, name, kind, warnings);
+        } else {
+            let old_path = new_path.replace("-", "_");
+            warnings.push(format!(
+                "`{old_path}` in the `{name}` {kind} was normalized to `{new_path}`"
+            ));
+            *canonical = Some(value);
+        }
+    }
+}
+
+fn normalize_dependency_aliases(
+    deps: Option<&mut BTreeMap<String, TomlDependency>>,
+    warnings: &mut Vec<String>,
+) {
+    for (name, dep) in deps.into_iter().flatten() {
+        if let TomlDependency::Detailed(details) = dep {
+            collapse_alias(
+                &mut details.default_features,
+                &mut details.default_features2,
+                "default-features",
+                name,
+                "dependency",
+                warnings,
+            );
+        }
+    }
+}
+
+/// The lowest version allowed by a `rust-version` requirement, or `0.0.0` if
+/// it has no lower bound.
+pub(crate) fn rust_version_lower_bound(req: &semver::VersionReq) -> semver::Version {
+    req.comparators
+        .iter()
+        .filter_map(|c| {
+            let (minor, patch) = (c.minor.unwrap_or(0), c.patch.unwrap_or(0));
+            match c.op {
+                semver::Op::Less | semver::Op::LessEq => None,
+                // `>1.70` is `>=1.71.0`, and `>1.70.1` is `>=1.70.2`.
+                semver::Op::Greater => Some(match (c.minor, c.patch) {
+                    (None, _) => semver::Version::new(c.major + 1, 0, 0),
+                    (Some(_), None) => semver::Version::new(c.major, minor + 1, 0),
+                    (Some(_), Some(_)) => semver::Version::new(c.major, minor, patch + 1),
+                }),
+                _ => Some(semver::Version::new(c.major, minor, patch)),
+            }
+        })
+        .max()
+        .unwrap_or_else(|| semver::Version::new(0, 0, 0))
+}
+
+/// Recursively sorts the keys of every table in `value`.
+fn sort_toml_tables(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => {
+            let mut entries: Vec<_> = std::mem::replace(table, toml::value::Table::new())
+                .into_iter()
+                .collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (_, value) in &mut entries {
+                sort_toml_tables(value);
+            }
+            *table = entries.into_iter().collect();
+        }
+        toml::Value::Array(array) => array.iter_mut().for_each(sort_toml_tables),
+        _ => {}
+    }
 }
 
 type TomlLibTarget = TomlTarget;
@@ -223,7 +710,7 @@ type TomlExampleTarget = TomlTarget;
 type TomlTestTarget = TomlTarget;
 type TomlBenchTarget = TomlTarget;
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, PartialEq)]
 #[serde(untagged)]
 pub enum TomlDependency<P: Clone = String> {
     /// In the simple format, only a version is specified, eg.
@@ -269,14 +756,41 @@ impl<'de, P: Deserialize<'de> + Clone> de::Deserialize<'de> for TomlDependency<P
                 let details: IntermediateDependency<P> = IntermediateDependency::deserialize(mvd)?;
                 if let Some(workspace) = details.workspace {
                     if workspace {
+                        let disallowed: Vec<_> = [
+                            ("version", details.version.is_some()),
+                            ("registry", details.registry.is_some()),
+                            ("registry-index", details.registry_index.is_some()),
+                            ("path", details.path.is_some()),
+                            ("git", details.git.is_some()),
+                            ("branch", details.branch.is_some()),
+                            ("tag", details.tag.is_some()),
+                            ("rev", details.rev.is_some()),
+                        ]
+                        .iter()
+                        .filter(|(_, present)| *present)
+                        .map(|(key, _)| format!("`{}`", key))
+                        .collect();
+                        if !disallowed.is_empty() {
+                            return Err(de::Error::custom(format!(
+                                "{} cannot be used with `workspace = true`, \
+                                 set {} in `[workspace.dependencies]` instead",
+                                disallowed.join(", "),
+                                if disallowed.len() == 1 { "it" } else { "them" }
+                            )));
+                        }
                         Ok(TomlDependency::Workspace(TomlWorkspaceDependency {
                             workspace: true,
                             features: details.features,
+                            inherit_features: details.inherit_features,
                             optional: details.optional,
                         }))
                     } else {
                         return Err(de::Error::custom("workspace cannot be false"));
                     }
+                } else if details.inherit_features.is_some() {
+                    Err(de::Error::custom(
+                        "`inherit-features` may only be used with `workspace = true`",
+                    ))
                 } else {
                     Ok(TomlDependency::Detailed(DetailedTomlDependency {
                         version: details.version,
@@ -305,6 +819,76 @@ impl<'de, P: Deserialize<'de> + Clone> de::Deserialize<'de> for TomlDependency<P
     }
 }
 
+/// Creates a [`TomlDependency::Simple`] from a version requirement.
+///
+/// ```
+/// use cargo::util::toml::TomlDependency;
+///
+/// let d: TomlDependency = "1.0".into();
+/// assert!(matches!(d, TomlDependency::Simple(v) if v == "1.0"));
+/// ```
+impl<P: Clone> From<&str> for TomlDependency<P> {
+    fn from(version: &str) -> Self {
+        TomlDependency::Simple(version.to_owned())
+    }
+}
+
+impl<P: Clone> From<DetailedTomlDependency<P>> for TomlDependency<P> {
+    fn from(details: DetailedTomlDependency<P>) -> Self {
+        TomlDependency::Detailed(details)
+    }
+}
+
+/// Where a [`TomlDependency`] would be fetched from, as far as can be told
+/// from its TOML fields alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DependencySourceKind {
+    /// The default registry, crates.io unless replaced.
+    Registry,
+    /// A registry named by `registry` or `registry-index`.
+    AltRegistry,
+    Git,
+    Path,
+    /// `dep.workspace = true`, the source comes from `[workspace.dependencies]`.
+    Workspace,
+}
+
+impl<P: Clone> TomlDependency<P> {
+    /// Classifies where this dependency comes from without building a
+    /// [`Dependency`].
+    ///
+    /// A detailed dependency naming more than one source is classified by the
+    /// first of `git`, `path`, and `registry` that it sets.
+    pub fn source_kind(&self) -> DependencySourceKind {
+        match self {
+            TomlDependency::Simple(..) => DependencySourceKind::Registry,
+            TomlDependency::Workspace(_) => DependencySourceKind::Workspace,
+            TomlDependency::Detailed(d) => {
+                if d.git.is_some() {
+                    DependencySourceKind::Git
+                } else if d.path.is_some() {
+                    DependencySourceKind::Path
+                } else if d.registry.is_some() || d.registry_index.is_some() {
+                    DependencySourceKind::AltRegistry
+                } else {
+                    DependencySourceKind::Registry
+                }
+            }
+        }
+    }
+
+    /// The features listed in this dependency's `features`, empty for the
+    /// plain `"version"` form.
+    pub fn declared_features(&self) -> &[String] {
+        let features = match self {
+            TomlDependency::Simple(..) => None,
+            TomlDependency::Detailed(d) => d.features.as_ref(),
+            TomlDependency::Workspace(w) => w.features.as_ref(),
+        };
+        features.map_or(&[], |f| f.as_slice())
+    }
+}
+
 pub trait ResolveToPath {
     fn resolve(&self, config: &Config) -> PathBuf;
 }
@@ -327,6 +911,7 @@ impl ResolveToPath for ConfigRelativePath {
 #[serde(rename_all = "kebab-case")]
 pub struct IntermediateDependency<P = String> {
     workspace: Option<bool>,
+    inherit_features: Option<bool>,
     version: Option<String>,
     registry: Option<String>,
     registry_index: Option<String>,
@@ -347,14 +932,18 @@ pub struct IntermediateDependency<P = String> {
     target: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
 pub struct TomlWorkspaceDependency {
     workspace: bool,
     features: Option<Vec<String>>,
+    /// When `false`, `features` replaces the features declared in
+    /// `[workspace.dependencies]` instead of adding to them.
+    inherit_features: Option<bool>,
     optional: Option<bool>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct DetailedTomlDependency<P: Clone = String> {
     version: Option<String>,
@@ -389,6 +978,19 @@ pub struct DetailedTomlDependency<P: Clone = String> {
     target: Option<String>,
 }
 
+impl<P: Clone> DetailedTomlDependency<P> {
+    /// The git reference selected by `branch`, `tag` or `rev`, in that order
+    /// of preference.
+    fn git_reference(&self) -> GitReference {
+        self.branch
+            .clone()
+            .map(GitReference::Branch)
+            .or_else(|| self.tag.clone().map(GitReference::Tag))
+            .or_else(|| self.rev.clone().map(GitReference::Rev))
+            .unwrap_or(GitReference::DefaultBranch)
+    }
+}
+
 // Explicit implementation so we avoid pulling in P: Default
 impl<P: Clone> Default for DetailedTomlDependency<P> {
     fn default() -> Self {
@@ -414,8 +1016,259 @@ impl<P: Clone> Default for DetailedTomlDependency<P> {
     }
 }
 
+/// The Cargo feature each unstable edition requires. A new edition gets an
+/// entry here, which is removed again when the edition is stabilized.
+const UNSTABLE_EDITIONS: &[(Edition, fn() -> &'static Feature)] = &[];
+
+/// Requires the Cargo feature that gates `edition`, if it is unstable.
+fn gate_edition(edition: Edition, features: &Features) -> CargoResult<()> {
+    gate_edition_with(edition, features, UNSTABLE_EDITIONS)
+}
+
+fn gate_edition_with(
+    edition: Edition,
+    features: &Features,
+    gates: &[(Edition, fn() -> &'static Feature)],
+) -> CargoResult<()> {
+    match gates.iter().find(|(gated, _)| *gated == edition) {
+        Some((_, feature)) => features.require(feature()),
+        None if edition.is_stable() => Ok(()),
+        // Guard in case someone forgets to add the edition to `UNSTABLE_EDITIONS`.
+        None => Err(util::errors::internal(format!(
+            "edition {} should be gated",
+            edition
+        ))),
+    }
+}
+
+/// Warns about `rustflags` entries that were most likely split or joined by
+/// mistake, since each entry is passed to rustc as a single argument.
+fn lint_rustflags(name: &str, rustflags: &[InternedString], warnings: &mut Vec<String>) {
+    // Flags whose value may be given as the following argument.
+    const TAKES_VALUE: &[&str] = &["-C", "-Z", "-L", "-l", "-A", "-W", "-D", "-F", "--cfg"];
+    // Options whose value is itself a space-separated list of arguments.
+    const LIST_VALUED: &[&str] = &["link-args", "pre-link-args", "llvm-args"];
+    let is_bare = |arg: &str| !arg.starts_with('-');
+    let has_list_value = |arg: &str| {
+        let option = arg.trim_start_matches("-C").trim_start_matches("-Z");
+        matches!(option.split_once('='), Some((key, _)) if LIST_VALUED.contains(&key))
+    };
+    for (i, flag) in rustflags.iter().enumerate() {
+        let mut words = flag.split_whitespace();
+        if let (Some(first), Some(_)) = (words.next(), words.clone().next()) {
+            if TAKES_VALUE.contains(&first)
+                || (!has_list_value(first) && words.any(|word| !is_bare(word)))
+            {
+                warnings.push(format!(
+                    "`profile.{}.rustflags` entry `{}` looks like more than one flag; \
+                     each entry is passed to rustc as a single argument, \
+                     so give every flag its own entry",
+                    name, flag
+                ));
+                continue;
+            }
+        }
+        // `-C opt-level 3` or `-Copt-level 3` instead of `-C opt-level=3`.
+        let (option, value) = match flag.as_str() {
+            "-C" => (rustflags.get(i + 1), rustflags.get(i + 2)),
+            f if f.starts_with("-C") => (Some(flag), rustflags.get(i + 1)),
+            _ => continue,
+        };
+        if let (Some(option), Some(value)) = (option, value) {
+            let option = option.trim_start_matches("-C");
+            if !option.contains('=') && is_bare(option) && is_bare(value) {
+                warnings.push(format!(
+                    "`profile.{}.rustflags` passes `{}` and `{}` as separate arguments \
+                     to `-C`, which rustc does not accept; did you mean `-C {}={}`?",
+                    name, option, value, option, value
+                ));
+            }
+        }
+    }
+}
+
+/// Warns about features named after a dependency that isn't optional.
+///
+/// Only optional dependencies share their name with a feature, so such a
+/// feature has nothing to do with the dependency, which is rarely intended.
+fn lint_features_shadowing_deps(
+    features: &BTreeMap<InternedString, Vec<InternedString>>,
+    deps: &[Dependency],
+    warnings: &mut Vec<String>,
+) {
+    for feature in features.keys() {
+        let mut same_name = deps.iter().filter(|d| d.name_in_toml() == *feature);
+        let mut same_name_optional = same_name.clone().filter(|d| d.is_optional());
+        if same_name.next().is_some() && same_name_optional.next().is_none() {
+            warnings.push(format!(
+                "feature `{0}` has the same name as the non-optional dependency `{0}`; \
+                 enabling the feature does not affect the dependency, \
+                 consider renaming the feature or making the dependency optional",
+                feature
+            ));
+        }
+    }
+}
+
+/// Whether `author` is either a plain name or `Name <email@domain>`.
+fn is_valid_author(author: &str) -> bool {
+    let (name, email) = match author.trim().strip_suffix('>') {
+        Some(rest) => match rest.rsplit_once('<') {
+            Some(split) => split,
+            None => return false,
+        },
+        None => return !author.contains(['<', '>']),
+    };
+    let (local, domain) = match email.split_once('@') {
+        Some(split) => split,
+        None => return false,
+    };
+    !name.trim().is_empty()
+        && !name.contains(['<', '>'])
+        && !local.is_empty()
+        && !email.contains(|c: char| c.is_whitespace() || c == '>')
+        && !domain.contains('@')
+        && domain.contains('.')
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+/// Checks `package.keywords` against the rules crates.io enforces: at most
+/// five keywords, each at most 20 characters of `[a-zA-Z0-9_-]` that start
+/// with a letter.
+fn validate_keywords(keywords: &[String]) -> CargoResult<()> {
+    const MAX_KEYWORDS: usize = 5;
+    const MAX_KEYWORD_LEN: usize = 20;
+
+    if keywords.len() > MAX_KEYWORDS {
+        bail!(
+            "package has {} keywords, but at most {} are allowed",
+            keywords.len(),
+            MAX_KEYWORDS
+        );
+    }
+    for keyword in keywords {
+        let mut chars = keyword.chars();
+        let valid = chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            bail!(
+                "invalid keyword `{}`, keywords must start with a letter and \
+                 contain only letters, numbers, `_`, and `-`",
+                keyword
+            );
+        }
+        if keyword.len() > MAX_KEYWORD_LEN {
+            bail!(
+                "keyword `{}` is longer than {} characters",
+                keyword,
+                MAX_KEYWORD_LEN
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks that there are at most five `package.categories`, each shaped like a
+/// crates.io category slug. Whether the category exists isn't checked, since
+/// the list of categories changes over time.
+fn validate_categories(categories: &[String]) -> CargoResult<()> {
+    const MAX_CATEGORIES: usize = 5;
+
+    if categories.len() > MAX_CATEGORIES {
+        bail!(
+            "package has {} categories, but at most {} are allowed",
+            categories.len(),
+            MAX_CATEGORIES
+        );
+    }
+    for category in categories {
+        let valid = category.split("::").all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        });
+        if !valid {
+            bail!(
+                "invalid category `{}`, categories are lowercase slugs separated \
+                 by `::`, such as `development-tools::cargo-plugins`",
+                category
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every entry of a `build = [..]` list is a `.rs` file.
+fn validate_build_scripts(scripts: &[String]) -> CargoResult<()> {
+    for script in scripts {
+        if Path::new(script).extension().and_then(|e| e.to_str()) != Some("rs") {
+            bail!("`build` entry `{}` is not a path to a `.rs` file", script);
+        }
+    }
+    Ok(())
+}
+
+/// Resolves `package.edition`, including the
+/// `{ workspace = true, at-least = ".." }` form.
+fn resolve_edition<E: Into<anyhow::Error>>(
+    mut edition: MaybeWorkspace<String>,
+    features: &Features,
+    get_ws_edition: impl FnOnce() -> Result<String, E>,
+) -> CargoResult<Edition> {
+    let at_least = edition.take_at_least();
+    let edition = edition.resolve(features, "edition", get_ws_edition)?;
+    let edition: Edition = edition
+        .parse()
+        .map_err(|_| invalid_edition_error(&edition))?;
+    let at_least = match at_least {
+        Some(at_least) => at_least,
+        None => return Ok(edition),
+    };
+    let at_least: Edition = at_least
+        .parse()
+        .map_err(|_| invalid_edition_error(&at_least))?;
+    if at_least < edition {
+        bail!(
+            "`edition.at-least` is `{}`, which is older than the \
+             edition `{}` inherited from the workspace",
+            at_least,
+            edition
+        );
+    }
+    Ok(at_least)
+}
+
+/// Builds the error for an `edition` value that isn't a known edition,
+/// listing every edition this version of Cargo understands.
+fn invalid_edition_error(edition: &str) -> anyhow::Error {
+    let valid = Edition::CLI_VALUES
+        .iter()
+        .map(|e| format!("`{}`", e))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let latest = Edition::LATEST_STABLE.to_string();
+    let is_future_year = edition.len() == latest.len()
+        && edition.bytes().all(|b| b.is_ascii_digit())
+        && edition > latest.as_str();
+    if is_future_year {
+        anyhow!(
+            "invalid `edition` value `{}`, this version of Cargo is older than \
+             that edition and only supports {}",
+            edition,
+            valid
+        )
+    } else {
+        anyhow!(
+            "invalid `edition` value `{}`, expected one of {}",
+            edition,
+            valid
+        )
+    }
+}
+
 /// This type is used to deserialize `Cargo.toml` files.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct TomlManifest {
     cargo_features: Option<Vec<String>>,
@@ -434,7 +1287,7 @@ pub struct TomlManifest {
     build_dependencies: Option<BTreeMap<String, TomlDependency>>,
     #[serde(rename = "build_dependencies")]
     build_dependencies2: Option<BTreeMap<String, TomlDependency>>,
-    features: Option<BTreeMap<InternedString, Vec<InternedString>>>,
+    features: Option<TomlFeatures>,
     target: Option<BTreeMap<String, TomlPlatform>>,
     replace: Option<BTreeMap<String, TomlDependency>>,
     patch: Option<BTreeMap<String, BTreeMap<String, TomlDependency>>>,
@@ -442,24 +1295,189 @@ pub struct TomlManifest {
     badges: Option<MaybeWorkspace<BTreeMap<String, BTreeMap<String, String>>>>,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default)]
-pub struct TomlProfiles(BTreeMap<InternedString, TomlProfile>);
+/// Represents the `[features]` table of a `Cargo.toml`.
+///
+/// Besides the features themselves, the table may contain `workspace = true`
+/// to inherit the features defined in `[workspace.package.features]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TomlFeatures {
+    workspace: Option<bool>,
+    features: BTreeMap<InternedString, Vec<InternedString>>,
+}
 
-impl TomlProfiles {
-    pub fn get_all(&self) -> &BTreeMap<InternedString, TomlProfile> {
-        &self.0
+impl TomlFeatures {
+    pub fn get_all(&self) -> &BTreeMap<InternedString, Vec<InternedString>> {
+        &self.features
     }
 
-    pub fn get(&self, name: &str) -> Option<&TomlProfile> {
-        self.0.get(name)
+    /// Resolves `workspace = true`, merging the inherited features with the
+    /// ones defined locally.
+    fn resolve<E: Into<anyhow::Error>>(
+        self,
+        cargo_features: &Features,
+        get_ws_features: impl FnOnce() -> Result<BTreeMap<InternedString, Vec<InternedString>>, E>,
+    ) -> CargoResult<BTreeMap<InternedString, Vec<InternedString>>> {
+        let workspace = match self.workspace {
+            Some(workspace) => workspace,
+            None => return Ok(self.features),
+        };
+        let mut features = MaybeWorkspace::Workspace(TomlWorkspaceField {
+            workspace,
+            ..Default::default()
+        })
+        .resolve(cargo_features, "features", get_ws_features)?;
+        for (name, value) in self.features {
+            if features.contains_key(&name) {
+                bail!(
+                    "feature `{}` is defined in `[features]` but is also inherited \
+                     from the workspace root manifest's `workspace.package.features`",
+                    name
+                );
+            }
+            features.insert(name, value);
+        }
+        Ok(features)
+    }
+}
+
+impl From<BTreeMap<InternedString, Vec<InternedString>>> for TomlFeatures {
+    fn from(features: BTreeMap<InternedString, Vec<InternedString>>) -> Self {
+        TomlFeatures {
+            workspace: None,
+            features,
+        }
+    }
+}
+
+impl<'de> de::Deserialize<'de> for TomlFeatures {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = TomlFeatures;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a table of features")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+            where
+                V: de::MapAccess<'de>,
+            {
+                #[derive(Deserialize)]
+                #[serde(untagged, expecting = "a boolean or an array of feature names")]
+                enum WorkspaceOrFeature {
+                    Workspace(bool),
+                    Feature(Vec<InternedString>),
+                }
+
+                let mut result = TomlFeatures::default();
+                while let Some(name) = map.next_key::<InternedString>()? {
+                    if name == "workspace" {
+                        match map.next_value()? {
+                            WorkspaceOrFeature::Workspace(workspace) => {
+                                result.workspace = Some(workspace);
+                            }
+                            WorkspaceOrFeature::Feature(value) => {
+                                result.features.insert(name, value);
+                            }
+                        }
+                    } else {
+                        let value = map.next_value()?;
+                        result.features.insert(name, value);
+                    }
+                }
+                Ok(result)
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+impl ser::Serialize for TomlFeatures {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        use ser::SerializeMap;
+
+        let len = self.features.len() + usize::from(self.workspace.is_some());
+        let mut map = serializer.serialize_map(Some(len))?;
+        if let Some(workspace) = self.workspace {
+            map.serialize_entry("workspace", &workspace)?;
+        }
+        for (name, value) in &self.features {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TomlProfiles(BTreeMap<InternedString, TomlProfile>);
+
+impl TomlProfiles {
+    pub fn get_all(&self) -> &BTreeMap<InternedString, TomlProfile> {
+        &self.0
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TomlProfile> {
+        self.0.get(name)
     }
 
     pub fn validate(&self, features: &Features, warnings: &mut Vec<String>) -> CargoResult<()> {
         for (name, profile) in &self.0 {
             profile.validate(name, features, warnings)?;
+            if features.is_enabled(Feature::lint_release_overflow_checks())
+                && profile.overflow_checks == Some(true)
+                && self.inherited_release_overflow_checks(name) == Some(false)
+            {
+                warnings.push(format!(
+                    "profile `{}` inherits from `release` but sets `overflow-checks = true`, \
+                     which `release` turns off. Remove it unless the checks are wanted \
+                     in this profile.",
+                    name
+                ));
+            }
         }
         Ok(())
     }
+
+    /// The `overflow-checks` value `name` would get from its parents if it
+    /// didn't set one, or `None` if `name` doesn't end up inheriting from
+    /// `release`. Follows `inherits` through the profiles in this manifest
+    /// and the built-in defaults.
+    ///
+    /// `release` itself doesn't count, and neither does a chain that is
+    /// broken or loops, which is reported when the profiles are loaded.
+    fn inherited_release_overflow_checks(&self, name: &str) -> Option<bool> {
+        let mut seen = HashSet::new();
+        let mut current = name;
+        let mut inherited = None;
+        while seen.insert(current) {
+            let parent = match self.0.get(current).and_then(|p| p.inherits) {
+                Some(inherits) => inherits.as_str(),
+                None => match current {
+                    "bench" => "release",
+                    "test" | "doc" => "dev",
+                    _ => return None,
+                },
+            };
+            if inherited.is_none() {
+                inherited = self.0.get(parent).and_then(|p| p.overflow_checks);
+            }
+            match parent {
+                "release" => return Some(inherited.unwrap_or(false)),
+                "dev" => return None,
+                _ => current = parent,
+            }
+        }
+        None
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -492,6 +1510,13 @@ impl<'de> de::Deserialize<'de> for TomlOptLevel {
             {
                 if value == "s" || value == "z" {
                     Ok(TomlOptLevel(value.to_string()))
+                } else if let Ok(level) = value.parse::<u32>() {
+                    Err(E::custom(format!(
+                        "must be `0`, `1`, `2`, `3`, `s` or `z`, \
+                         but found the string: \"{}\"; numeric levels are written \
+                         without quotes, as in `opt-level = {}`",
+                        value, level
+                    )))
                 } else {
                     Err(E::custom(format!(
                         "must be `0`, `1`, `2`, `3`, `s` or `z`, \
@@ -518,11 +1543,102 @@ impl ser::Serialize for TomlOptLevel {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
-#[serde(untagged, expecting = "expected a boolean or an integer")]
-pub enum U32OrBool {
-    U32(u32),
+/// The `debug` setting of a profile.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TomlDebugInfo {
+    /// `debug = 0`, `1` or `2`.
+    Level(u32),
+    /// `debug = true` or `false`.
     Bool(bool),
+    /// A named level like `debug = "line-tables-only"`.
+    Named(&'static str),
+}
+
+/// The accepted names for `debug`, and the `-C debuginfo` level they map to.
+const NAMED_DEBUG_LEVELS: &[(&str, u32)] = &[
+    ("none", 0),
+    ("line-directives-only", 1),
+    ("line-tables-only", 1),
+    ("limited", 1),
+    ("full", 2),
+];
+
+impl TomlDebugInfo {
+    /// The `-C debuginfo` level to pass to rustc, or `None` to not pass one.
+    pub fn level(&self) -> Option<u32> {
+        match *self {
+            TomlDebugInfo::Level(level) => Some(level),
+            TomlDebugInfo::Bool(true) => Some(2),
+            TomlDebugInfo::Bool(false) => None,
+            TomlDebugInfo::Named(name) => NAMED_DEBUG_LEVELS
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, level)| *level),
+        }
+    }
+}
+
+impl<'de> de::Deserialize<'de> for TomlDebugInfo {
+    fn deserialize<D>(d: D) -> Result<TomlDebugInfo, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = TomlDebugInfo;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a boolean, an integer or a debuginfo level")
+            }
+
+            fn visit_bool<E>(self, value: bool) -> Result<TomlDebugInfo, E>
+            where
+                E: de::Error,
+            {
+                Ok(TomlDebugInfo::Bool(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<TomlDebugInfo, E>
+            where
+                E: de::Error,
+            {
+                u32::try_from(value)
+                    .map(TomlDebugInfo::Level)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<TomlDebugInfo, E>
+            where
+                E: de::Error,
+            {
+                match NAMED_DEBUG_LEVELS.iter().find(|(name, _)| *name == value) {
+                    Some((name, _)) => Ok(TomlDebugInfo::Named(name)),
+                    None => Err(E::custom(format!(
+                        "must be a boolean, an integer, or one of `none`, \
+                         `line-directives-only`, `line-tables-only`, `limited` or `full`, \
+                         but found the string: \"{}\"",
+                        value
+                    ))),
+                }
+            }
+        }
+
+        d.deserialize_any(Visitor)
+    }
+}
+
+impl ser::Serialize for TomlDebugInfo {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match *self {
+            TomlDebugInfo::Level(level) => level.serialize(serializer),
+            TomlDebugInfo::Bool(b) => b.serialize(serializer),
+            TomlDebugInfo::Named(name) => name.serialize(serializer),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, Default, Eq, PartialEq)]
@@ -532,7 +1648,7 @@ pub struct TomlProfile {
     pub lto: Option<StringOrBool>,
     pub codegen_backend: Option<InternedString>,
     pub codegen_units: Option<u32>,
-    pub debug: Option<U32OrBool>,
+    pub debug: Option<TomlDebugInfo>,
     pub split_debuginfo: Option<String>,
     pub debug_assertions: Option<bool>,
     pub rpath: Option<bool>,
@@ -591,21 +1707,63 @@ impl fmt::Display for ProfilePackageSpec {
 }
 
 impl TomlProfile {
+    /// Returns the documented default settings of a built-in profile, or
+    /// `None` if `name` is not one of `dev`, `release`, `test`, or `bench`.
+    ///
+    /// `test` and `bench` have the same settings as `dev` and `release`,
+    /// which they inherit from.
+    ///
+    /// See https://doc.rust-lang.org/cargo/reference/profiles.html#default-profiles
+    pub fn builtin(name: &str) -> Option<TomlProfile> {
+        let profile = match name {
+            "dev" | "test" => TomlProfile {
+                opt_level: Some(TomlOptLevel("0".to_string())),
+                debug: Some(TomlDebugInfo::Bool(true)),
+                debug_assertions: Some(true),
+                overflow_checks: Some(true),
+                lto: Some(StringOrBool::Bool(false)),
+                panic: Some("unwind".to_string()),
+                incremental: Some(true),
+                codegen_units: Some(256),
+                rpath: Some(false),
+                ..Default::default()
+            },
+            "release" | "bench" => TomlProfile {
+                opt_level: Some(TomlOptLevel("3".to_string())),
+                debug: Some(TomlDebugInfo::Bool(false)),
+                debug_assertions: Some(false),
+                overflow_checks: Some(false),
+                lto: Some(StringOrBool::Bool(false)),
+                panic: Some("unwind".to_string()),
+                incremental: Some(false),
+                codegen_units: Some(16),
+                rpath: Some(false),
+                ..Default::default()
+            },
+            _ => return None,
+        };
+        Some(profile)
+    }
+
     pub fn validate(
         &self,
         name: &str,
         features: &Features,
         warnings: &mut Vec<String>,
     ) -> CargoResult<()> {
-        self.validate_profile(name, features)?;
+        self.validate_profile(name, features, warnings)?;
         if let Some(ref profile) = self.build_override {
             profile.validate_override("build-override")?;
-            profile.validate_profile(&format!("{name}.build-override"), features)?;
+            profile.validate_profile(&format!("{name}.build-override"), features, warnings)?;
         }
         if let Some(ref packages) = self.package {
             for (override_name, profile) in packages {
                 profile.validate_override("package")?;
-                profile.validate_profile(&format!("{name}.package.{override_name}"), features)?;
+                profile.validate_profile(
+                    &format!("{name}.package.{override_name}"),
+                    features,
+                    warnings,
+                )?;
             }
         }
 
@@ -655,6 +1813,25 @@ impl TomlProfile {
             }
         }
 
+        if self.codegen_units == Some(1) && self.incremental == Some(true) {
+            warnings.push(format!(
+                "profile `{}` sets both `codegen-units = 1` and `incremental = true`; \
+                 incremental compilation splits crates into more codegen units, \
+                 so `codegen-units = 1` will not give single-unit codegen",
+                name
+            ));
+        }
+
+        if let Some(split_debuginfo) = &self.split_debuginfo {
+            if !matches!(split_debuginfo.as_str(), "off" | "packed" | "unpacked") {
+                bail!(
+                    "`split-debuginfo` setting of `{}` is not a valid setting, \
+                     must be `off`, `packed`, or `unpacked`",
+                    split_debuginfo
+                );
+            }
+        }
+
         if let Some(StringOrBool::String(arg)) = &self.lto {
             if arg == "true" || arg == "false" {
                 bail!(
@@ -750,7 +1927,12 @@ impl TomlProfile {
     /// Validates a profile.
     ///
     /// This is a shallow check, which is reused for the profile itself and any overrides.
-    fn validate_profile(&self, name: &str, features: &Features) -> CargoResult<()> {
+    fn validate_profile(
+        &self,
+        name: &str,
+        features: &Features,
+        warnings: &mut Vec<String>,
+    ) -> CargoResult<()> {
         if let Some(codegen_backend) = &self.codegen_backend {
             features.require(Feature::codegen_backend())?;
             if codegen_backend.contains(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
@@ -761,8 +1943,9 @@ impl TomlProfile {
                 );
             }
         }
-        if self.rustflags.is_some() {
+        if let Some(rustflags) = &self.rustflags {
             features.require(Feature::profile_rustflags())?;
+            lint_rustflags(name, rustflags, warnings);
         }
         Ok(())
     }
@@ -775,16 +1958,150 @@ impl TomlProfile {
         if self.build_override.is_some() {
             bail!("build-override profiles cannot be nested");
         }
-        if self.panic.is_some() {
-            bail!("`panic` may not be specified in a `{}` profile", which)
+        // Report every disallowed field at once so they can all be fixed
+        // in one go.
+        let disallowed: Vec<_> = [
+            ("panic", self.panic.is_some()),
+            ("lto", self.lto.is_some()),
+            ("rpath", self.rpath.is_some()),
+        ]
+        .iter()
+        .filter(|(_, set)| *set)
+        .map(|(name, _)| format!("`{}`", name))
+        .collect();
+        match disallowed.as_slice() {
+            [] => Ok(()),
+            [one] => bail!("{} may not be specified in a `{}` profile", one, which),
+            [init @ .., last] => bail!(
+                "{} and {} may not be specified in a `{}` profile",
+                init.join(", "),
+                last,
+                which
+            ),
+        }
+    }
+
+    /// Whether this profile doesn't set anything, so leaving it out changes
+    /// nothing. `package` and `build-override` tables that only hold empty
+    /// profiles count as empty.
+    pub fn is_empty(&self) -> bool {
+        let settings = TomlProfile {
+            package: None,
+            build_override: None,
+            ..self.clone()
+        };
+        settings == TomlProfile::default()
+            && self
+                .package
+                .as_ref()
+                .map_or(true, |p| p.values().all(TomlProfile::is_empty))
+            && self.build_override.as_ref().map_or(true, |p| p.is_empty())
+    }
+
+    /// Serializes only the settings of this profile that differ from `base`,
+    /// i.e. what this profile actually overrides.
+    pub fn to_minimal_toml(&self, base: &TomlProfile) -> CargoResult<String> {
+        Ok(toml::to_string(&self.minimized(base))?)
+    }
+
+    fn minimized(&self, base: &TomlProfile) -> TomlProfile {
+        let mut minimal = self.clone();
+        macro_rules! drop_unchanged {
+            ($($field:ident),*) => {$(
+                if minimal.$field == base.$field {
+                    minimal.$field = None;
+                }
+            )*};
         }
-        if self.lto.is_some() {
-            bail!("`lto` may not be specified in a `{}` profile", which)
+        drop_unchanged!(
+            opt_level,
+            lto,
+            codegen_backend,
+            codegen_units,
+            debug,
+            split_debuginfo,
+            debug_assertions,
+            rpath,
+            panic,
+            overflow_checks,
+            incremental,
+            dir_name,
+            inherits,
+            strip,
+            rustflags
+        );
+        minimal.package = self.package.as_ref().and_then(|packages| {
+            let empty = BTreeMap::new();
+            let base_packages = base.package.as_ref().unwrap_or(&empty);
+            let changed: BTreeMap<_, _> = packages
+                .iter()
+                .filter_map(|(spec, profile)| {
+                    let profile = match base_packages.get(spec) {
+                        Some(base) if base == profile => return None,
+                        Some(base) => profile.minimized(base),
+                        None => profile.clone(),
+                    };
+                    Some((spec.clone(), profile))
+                })
+                .collect();
+            (!changed.is_empty()).then(|| changed)
+        });
+        minimal.build_override = match (&self.build_override, &base.build_override) {
+            (Some(profile), Some(base)) if profile == base => None,
+            (Some(profile), Some(base)) => Some(Box::new(profile.minimized(base))),
+            (profile, None) => profile.clone(),
+            (None, Some(_)) => None,
+        };
+        minimal
+    }
+
+    /// Returns the settings that `[profile.*.package]` applies to `pkg`.
+    ///
+    /// `package."*"` is applied first and a spec matching `pkg` is merged on
+    /// top of it, so the specific spec wins. This mirrors how overrides are
+    /// applied to a dependency; `"*"` doesn't apply to workspace members.
+    ///
+    /// Fails if more than one spec matches `pkg`.
+    pub fn resolve_package_profile(
+        &self,
+        pkg: PackageId,
+        is_member: bool,
+    ) -> CargoResult<TomlProfile> {
+        let mut resolved = TomlProfile::default();
+        let overrides = match &self.package {
+            Some(overrides) => overrides,
+            None => return Ok(resolved),
+        };
+        if !is_member {
+            if let Some(all) = overrides.get(&ProfilePackageSpec::All) {
+                resolved.merge(all);
+            }
         }
-        if self.rpath.is_some() {
-            bail!("`rpath` may not be specified in a `{}` profile", which)
+        let matches: Vec<_> = overrides
+            .iter()
+            .filter_map(|(key, profile)| match key {
+                ProfilePackageSpec::Spec(spec) if spec.matches(pkg) => Some((spec, profile)),
+                _ => None,
+            })
+            .collect();
+        match matches[..] {
+            [] => {}
+            [(_, specific)] => resolved.merge(specific),
+            _ => {
+                let specs = matches
+                    .iter()
+                    .map(|(spec, _)| spec.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                bail!(
+                    "multiple package overrides match package `{}`\n\
+                     found package specs: {}",
+                    pkg,
+                    specs
+                );
+            }
         }
-        Ok(())
+        Ok(resolved)
     }
 
     /// Overwrite self's values with the given profile.
@@ -872,6 +2189,102 @@ impl TomlProfile {
             self.strip = Some(v.clone());
         }
     }
+
+    /// Same as [`TomlProfile::merge`], but returns the names of the fields
+    /// whose existing value was replaced by a different value from `profile`.
+    ///
+    /// Fields of nested profiles are prefixed with their table, such as
+    /// `build-override.opt-level` or `package.foo.debug`.
+    pub fn merge_reporting(&mut self, profile: &TomlProfile) -> Vec<String> {
+        let mut changed = Vec::new();
+        self.changed_fields(profile, "", &mut changed);
+        self.merge(profile);
+        changed
+    }
+
+    fn changed_fields(&self, profile: &TomlProfile, prefix: &str, changed: &mut Vec<String>) {
+        fn check<T: PartialEq>(
+            old: &Option<T>,
+            new: &Option<T>,
+            name: &str,
+            prefix: &str,
+            changed: &mut Vec<String>,
+        ) {
+            if let (Some(old), Some(new)) = (old, new) {
+                if old != new {
+                    changed.push(format!("{}{}", prefix, name));
+                }
+            }
+        }
+
+        let (old, new) = (self, profile);
+        check(&old.opt_level, &new.opt_level, "opt-level", prefix, changed);
+        check(&old.lto, &new.lto, "lto", prefix, changed);
+        check(
+            &old.codegen_backend,
+            &new.codegen_backend,
+            "codegen-backend",
+            prefix,
+            changed,
+        );
+        check(
+            &old.codegen_units,
+            &new.codegen_units,
+            "codegen-units",
+            prefix,
+            changed,
+        );
+        check(&old.debug, &new.debug, "debug", prefix, changed);
+        check(
+            &old.debug_assertions,
+            &new.debug_assertions,
+            "debug-assertions",
+            prefix,
+            changed,
+        );
+        check(
+            &old.split_debuginfo,
+            &new.split_debuginfo,
+            "split-debuginfo",
+            prefix,
+            changed,
+        );
+        check(&old.rpath, &new.rpath, "rpath", prefix, changed);
+        check(&old.panic, &new.panic, "panic", prefix, changed);
+        check(
+            &old.overflow_checks,
+            &new.overflow_checks,
+            "overflow-checks",
+            prefix,
+            changed,
+        );
+        check(
+            &old.incremental,
+            &new.incremental,
+            "incremental",
+            prefix,
+            changed,
+        );
+        check(&old.rustflags, &new.rustflags, "rustflags", prefix, changed);
+
+        if let (Some(self_package), Some(other_package)) = (&self.package, &profile.package) {
+            for (spec, other_pkg_profile) in other_package {
+                if let Some(p) = self_package.get(spec) {
+                    let prefix = format!("{}package.{}.", prefix, spec);
+                    p.changed_fields(other_pkg_profile, &prefix, changed);
+                }
+            }
+        }
+
+        if let (Some(self_bo), Some(other_bo)) = (&self.build_override, &profile.build_override) {
+            let prefix = format!("{}build-override.", prefix);
+            self_bo.changed_fields(other_bo, &prefix, changed);
+        }
+
+        check(&old.inherits, &new.inherits, "inherits", prefix, changed);
+        check(&old.dir_name, &new.dir_name, "dir-name", prefix, changed);
+        check(&old.strip, &new.strip, "strip", prefix, changed);
+    }
 }
 
 /// A StringOrVec can be parsed from either a TOML string or array,
@@ -919,6 +2332,34 @@ impl StringOrVec {
     }
 }
 
+/// The value of `license-file`.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(untagged, expecting = "expected a string or an array of strings")]
+pub enum TomlLicenseFile {
+    One(String),
+    /// Any number of license files, which requires the
+    /// `multiple-license-files` feature.
+    Many(Vec<String>),
+}
+
+impl TomlLicenseFile {
+    pub fn files(&self) -> &[String] {
+        match self {
+            TomlLicenseFile::One(file) => std::slice::from_ref(file),
+            TomlLicenseFile::Many(files) => files,
+        }
+    }
+
+    fn try_map(self, mut f: impl FnMut(String) -> CargoResult<String>) -> CargoResult<Self> {
+        Ok(match self {
+            TomlLicenseFile::One(file) => TomlLicenseFile::One(f(file)?),
+            TomlLicenseFile::Many(files) => {
+                TomlLicenseFile::Many(files.into_iter().map(f).collect::<CargoResult<_>>()?)
+            }
+        })
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(untagged, expecting = "expected a boolean or a string")]
 pub enum StringOrBool {
@@ -926,6 +2367,20 @@ pub enum StringOrBool {
     Bool(bool),
 }
 
+/// The value of `package.build`.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(
+    untagged,
+    expecting = "expected a boolean, a string or an array of strings"
+)]
+pub enum TomlBuild {
+    String(String),
+    Bool(bool),
+    /// More than one build script, which requires the
+    /// `multiple-build-scripts` feature.
+    Multiple(Vec<String>),
+}
+
 #[derive(PartialEq, Clone, Debug, Serialize)]
 #[serde(untagged)]
 pub enum VecStringOrBool {
@@ -967,47 +2422,109 @@ impl<'de> de::Deserialize<'de> for VecStringOrBool {
     }
 }
 
-fn version_trim_whitespace<'de, D>(
-    deserializer: D,
-) -> Result<MaybeWorkspace<semver::Version>, D::Error>
-where
-    D: de::Deserializer<'de>,
-{
-    struct Visitor;
+/// The value of `package.publish`.
+#[derive(PartialEq, Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum TomlPublishField {
+    /// `publish = false` or `publish = ["registry"]`.
+    Plain(VecStringOrBool),
+    /// `publish = { registry = false, reason = "internal only" }`, which
+    /// requires the `publish-reason` feature.
+    Detailed(TomlPublish),
+}
 
-    impl<'de> de::Visitor<'de> for Visitor {
-        type Value = MaybeWorkspace<semver::Version>;
+/// The detailed form of `package.publish`.
+#[derive(Deserialize, Serialize, PartialEq, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TomlPublish {
+    pub registry: bool,
+    pub reason: Option<String>,
+}
 
-        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-            formatter.write_str("SemVer version")
-        }
+impl<'de> de::Deserialize<'de> for TomlPublishField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Visitor;
 
-        fn visit_str<E>(self, string: &str) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            match string.trim().parse().map_err(de::Error::custom) {
-                Ok(parsed) => Ok(MaybeWorkspace::Defined(parsed)),
-                Err(e) => Err(e),
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = TomlPublishField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a boolean, vector of strings, or table")
             }
-        }
 
-        fn visit_map<V>(self, map: V) -> Result<Self::Value, V::Error>
-        where
-            V: de::MapAccess<'de>,
+            fn visit_seq<V>(self, v: V) -> Result<Self::Value, V::Error>
+            where
+                V: de::SeqAccess<'de>,
+            {
+                let seq = de::value::SeqAccessDeserializer::new(v);
+                Vec::deserialize(seq)
+                    .map(|v| TomlPublishField::Plain(VecStringOrBool::VecString(v)))
+            }
+
+            fn visit_bool<E>(self, b: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(TomlPublishField::Plain(VecStringOrBool::Bool(b)))
+            }
+
+            fn visit_map<V>(self, map: V) -> Result<Self::Value, V::Error>
+            where
+                V: de::MapAccess<'de>,
+            {
+                let mvd = de::value::MapAccessDeserializer::new(map);
+                TomlPublish::deserialize(mvd).map(TomlPublishField::Detailed)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+fn version_trim_whitespace<'de, D>(
+    deserializer: D,
+) -> Result<Option<MaybeWorkspace<semver::Version>>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    struct Visitor;
+
+    impl<'de> de::Visitor<'de> for Visitor {
+        type Value = MaybeWorkspace<semver::Version>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("SemVer version")
+        }
+
+        fn visit_str<E>(self, string: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            match string.trim().parse().map_err(de::Error::custom) {
+                Ok(parsed) => Ok(MaybeWorkspace::Defined(parsed)),
+                Err(e) => Err(e),
+            }
+        }
+
+        fn visit_map<V>(self, map: V) -> Result<Self::Value, V::Error>
+        where
+            V: de::MapAccess<'de>,
         {
             let mvd = de::value::MapAccessDeserializer::new(map);
             TomlWorkspaceField::deserialize(mvd).map(MaybeWorkspace::Workspace)
         }
     }
 
-    deserializer.deserialize_any(Visitor)
+    deserializer.deserialize_any(Visitor).map(Some)
 }
 
 /// Enum that allows for the parsing of `field.workspace = true` in a Cargo.toml
 ///
 /// It allows for things to be inherited from a workspace or defined as needed
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(untagged)]
 pub enum MaybeWorkspace<T> {
     Workspace(TomlWorkspaceField),
@@ -1015,22 +2532,32 @@ pub enum MaybeWorkspace<T> {
 }
 
 impl<T> MaybeWorkspace<T> {
-    fn resolve<'a>(
+    fn resolve<'a, E: Into<anyhow::Error>>(
         self,
         cargo_features: &Features,
         label: &str,
-        get_ws_field: impl FnOnce() -> CargoResult<T>,
+        get_ws_field: impl FnOnce() -> Result<T, E>,
     ) -> CargoResult<T> {
         match self {
             MaybeWorkspace::Defined(value) => Ok(value),
-            MaybeWorkspace::Workspace(TomlWorkspaceField { workspace: true }) => {
+            MaybeWorkspace::Workspace(TomlWorkspaceField {
+                at_least: Some(_), ..
+            }) => Err(anyhow!(
+                "`at-least` is only supported for `package.edition`, not `package.{}`",
+                label,
+            )),
+            MaybeWorkspace::Workspace(TomlWorkspaceField {
+                workspace: true, ..
+            }) => {
                 cargo_features.require(Feature::workspace_inheritance())?;
-                get_ws_field().context(format!(
+                get_ws_field().map_err(Into::into).context(format!(
                     "error inheriting `{}` from workspace root manifest's `workspace.package.{}`",
                     label, label
                 ))
             }
-            MaybeWorkspace::Workspace(TomlWorkspaceField { workspace: false }) => Err(anyhow!(
+            MaybeWorkspace::Workspace(TomlWorkspaceField {
+                workspace: false, ..
+            }) => Err(anyhow!(
                 "`workspace=false` is unsupported for `package.{}`",
                 label,
             )),
@@ -1042,11 +2569,108 @@ impl<T> MaybeWorkspace<T> {
             MaybeWorkspace::Defined(defined) => Some(defined),
         }
     }
+    /// Returns `true` for `{ workspace = .. }`, whether or not it is valid.
+    pub fn is_workspace(&self) -> bool {
+        matches!(self, MaybeWorkspace::Workspace(_))
+    }
+    /// Returns `true` if the value was written out in place.
+    pub fn is_defined(&self) -> bool {
+        matches!(self, MaybeWorkspace::Defined(_))
+    }
+    /// Returns the value if it was written out in place, discarding a
+    /// `{ workspace = .. }` field.
+    pub fn into_defined(self) -> Option<T> {
+        match self {
+            MaybeWorkspace::Workspace(_) => None,
+            MaybeWorkspace::Defined(defined) => Some(defined),
+        }
+    }
+    /// Removes and returns the `at-least` of `{ workspace = true, at-least = ".." }`.
+    fn take_at_least(&mut self) -> Option<String> {
+        match self {
+            MaybeWorkspace::Workspace(field) => field.at_least.take(),
+            MaybeWorkspace::Defined(_) => None,
+        }
+    }
+}
+
+/// Resolves a single `[package]` field against the workspace's
+/// [`InheritableFields`], without building the rest of the manifest.
+///
+/// `label` is the field's key, e.g. `"version"` or `"rust-version"`.
+pub fn resolve_field<T: InheritableValue>(
+    field: MaybeWorkspace<T>,
+    inheritable: &InheritableFields,
+    features: &Features,
+    label: &str,
+) -> CargoResult<T> {
+    field.resolve(features, label, || T::inherit(inheritable, label))
+}
+
+/// A type that a `[workspace.package]` field can be looked up as, by key.
+///
+/// Paths (`readme` and `license-file`) need the member's package root to be
+/// rewritten, so they can only be resolved through the full manifest.
+pub trait InheritableValue: Sized {
+    fn inherit(fields: &InheritableFields, label: &str) -> CargoResult<Self>;
+}
+
+impl InheritableValue for semver::Version {
+    fn inherit(fields: &InheritableFields, label: &str) -> CargoResult<Self> {
+        match label {
+            "version" => Ok(fields.version()?),
+            _ => bail!("`workspace.package.{}` is not a version", label),
+        }
+    }
+}
+
+impl InheritableValue for String {
+    fn inherit(fields: &InheritableFields, label: &str) -> CargoResult<Self> {
+        Ok(match label {
+            "description" => fields.description()?,
+            "homepage" => fields.homepage()?,
+            "documentation" => fields.documentation()?,
+            "license" => fields.license()?,
+            "repository" => fields.repository()?,
+            "edition" => fields.edition()?,
+            "rust-version" => fields.rust_version()?,
+            _ => bail!("`workspace.package.{}` is not a string", label),
+        })
+    }
+}
+
+impl InheritableValue for Vec<String> {
+    fn inherit(fields: &InheritableFields, label: &str) -> CargoResult<Self> {
+        Ok(match label {
+            "authors" => fields.authors()?,
+            "keywords" => fields.keywords()?,
+            "categories" => fields.categories()?,
+            "exclude" => fields.exclude()?,
+            "include" => fields.include()?,
+            _ => bail!("`workspace.package.{}` is not a list of strings", label),
+        })
+    }
+}
+
+impl InheritableValue for TomlPublishField {
+    fn inherit(fields: &InheritableFields, label: &str) -> CargoResult<Self> {
+        match label {
+            "publish" => Ok(fields.publish()?),
+            _ => bail!(
+                "`workspace.package.{}` is not a list of strings or a bool",
+                label
+            ),
+        }
+    }
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
 pub struct TomlWorkspaceField {
     workspace: bool,
+    /// `edition = { workspace = true, at-least = "2021" }` raises the
+    /// inherited edition to at least this one.
+    #[serde(rename = "at-least", default, skip_serializing_if = "Option::is_none")]
+    at_least: Option<String>,
 }
 
 /// Represents the `package`/`project` sections of a `Cargo.toml`.
@@ -1055,16 +2679,16 @@ pub struct TomlWorkspaceField {
 /// are serialized to a TOML file. For example, you cannot have values after
 /// the field `metadata`, since it is a table and values cannot appear after
 /// tables.
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct TomlProject {
     edition: Option<MaybeWorkspace<String>>,
     rust_version: Option<MaybeWorkspace<String>>,
     name: InternedString,
-    #[serde(deserialize_with = "version_trim_whitespace")]
-    version: MaybeWorkspace<semver::Version>,
+    #[serde(default, deserialize_with = "version_trim_whitespace")]
+    version: Option<MaybeWorkspace<semver::Version>>,
     authors: Option<MaybeWorkspace<Vec<String>>>,
-    build: Option<StringOrBool>,
+    build: Option<TomlBuild>,
     metabuild: Option<StringOrVec>,
     #[serde(rename = "default-target")]
     default_target: Option<String>,
@@ -1073,9 +2697,10 @@ pub struct TomlProject {
     links: Option<String>,
     exclude: Option<MaybeWorkspace<Vec<String>>>,
     include: Option<MaybeWorkspace<Vec<String>>>,
-    publish: Option<MaybeWorkspace<VecStringOrBool>>,
+    publish: Option<MaybeWorkspace<TomlPublishField>>,
     workspace: Option<String>,
     im_a_teapot: Option<bool>,
+    autolib: Option<bool>,
     autobins: Option<bool>,
     autoexamples: Option<bool>,
     autotests: Option<bool>,
@@ -1090,7 +2715,7 @@ pub struct TomlProject {
     keywords: Option<MaybeWorkspace<Vec<String>>>,
     categories: Option<MaybeWorkspace<Vec<String>>>,
     license: Option<MaybeWorkspace<String>>,
-    license_file: Option<MaybeWorkspace<String>>,
+    license_file: Option<MaybeWorkspace<TomlLicenseFile>>,
     repository: Option<MaybeWorkspace<String>>,
     resolver: Option<String>,
 
@@ -1099,7 +2724,7 @@ pub struct TomlProject {
     metadata: Option<toml::Value>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct TomlWorkspace {
     members: Option<Vec<String>>,
     #[serde(rename = "default-members")]
@@ -1116,8 +2741,42 @@ pub struct TomlWorkspace {
     metadata: Option<toml::Value>,
 }
 
+impl TomlWorkspace {
+    /// The `members` globs, as written.
+    pub fn members(&self) -> &[String] {
+        self.members.as_deref().unwrap_or_default()
+    }
+
+    /// The `default-members` paths, as written.
+    pub fn default_members(&self) -> &[String] {
+        self.default_members.as_deref().unwrap_or_default()
+    }
+
+    /// The `exclude` paths, as written.
+    pub fn exclude(&self) -> &[String] {
+        self.exclude.as_deref().unwrap_or_default()
+    }
+
+    /// The resolver behavior selected by `resolver`, if set.
+    pub fn resolve_behavior(&self) -> CargoResult<Option<ResolveBehavior>> {
+        self.resolver
+            .as_deref()
+            .map(ResolveBehavior::from_manifest)
+            .transpose()
+    }
+}
+
+/// A git dependency of a [`TomlManifest`], see [`TomlManifest::git_dependencies`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitDepInfo {
+    /// The dependency's key in its table.
+    pub name: String,
+    pub url: String,
+    pub reference: GitReference,
+}
+
 /// A group of fields that are inheritable by members of the workspace
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
 pub struct InheritableFields {
     // We use skip here since it will never be present when deserializing
     // and we don't want it present when serializing
@@ -1133,19 +2792,98 @@ pub struct InheritableFields {
     categories: Option<Vec<String>>,
     license: Option<String>,
     #[serde(rename = "license-file")]
-    license_file: Option<String>,
+    license_file: Option<TomlLicenseFile>,
     repository: Option<String>,
-    publish: Option<VecStringOrBool>,
+    publish: Option<TomlPublishField>,
     edition: Option<String>,
     badges: Option<BTreeMap<String, BTreeMap<String, String>>>,
     exclude: Option<Vec<String>>,
     include: Option<Vec<String>>,
     #[serde(rename = "rust-version")]
     rust_version: Option<String>,
+    features: Option<BTreeMap<InternedString, Vec<InternedString>>>,
     // We use skip here since it will never be present when deserializing
     // and we don't want it present when serializing
     #[serde(skip)]
     ws_root: PathBuf,
+    // Set by `overlay` for paths that came from the base layer, which are
+    // relative to that layer's root instead of `ws_root`.
+    #[serde(skip)]
+    readme_root: Option<PathBuf>,
+    #[serde(skip)]
+    license_file_root: Option<PathBuf>,
+    #[serde(skip)]
+    dependency_roots: BTreeMap<String, PathBuf>,
+}
+
+/// The keys accepted in `[workspace.package]`.
+const INHERITABLE_FIELDS: &[&str] = &[
+    "version",
+    "authors",
+    "description",
+    "homepage",
+    "documentation",
+    "readme",
+    "keywords",
+    "categories",
+    "license",
+    "license-file",
+    "repository",
+    "publish",
+    "edition",
+    "badges",
+    "exclude",
+    "include",
+    "rust-version",
+    "features",
+];
+
+/// The reason a field couldn't be inherited from [`InheritableFields`].
+#[derive(Debug)]
+pub enum InheritanceError {
+    /// The field is not set in `[workspace.package]`.
+    FieldNotDefined(&'static str),
+    /// There is no `[workspace.dependencies]` table.
+    DependenciesNotDefined,
+    /// The dependency is not in `[workspace.dependencies]`.
+    DependencyNotFound(String),
+    /// A path from `[workspace.package]` couldn't be made relative to the
+    /// member's package root.
+    PathNotResolved(anyhow::Error),
+    /// The workspace root manifest couldn't be found or loaded.
+    WorkspaceNotLoaded(anyhow::Error),
+}
+
+impl std::error::Error for InheritanceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InheritanceError::PathNotResolved(e) | InheritanceError::WorkspaceNotLoaded(e) => {
+                e.source()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InheritanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InheritanceError::FieldNotDefined(field) => {
+                write!(f, "`workspace.package.{}` was not defined", field)
+            }
+            InheritanceError::DependenciesNotDefined => {
+                f.write_str("`workspace.dependencies` was not defined")
+            }
+            InheritanceError::DependencyNotFound(name) => write!(
+                f,
+                "`dependency.{}` was not found in `workspace.dependencies`",
+                name
+            ),
+            InheritanceError::PathNotResolved(e) | InheritanceError::WorkspaceNotLoaded(e) => {
+                e.fmt(f)
+            }
+        }
+    }
 }
 
 impl InheritableFields {
@@ -1157,149 +2895,250 @@ impl InheritableFields {
         self.ws_root = ws_root;
     }
 
-    pub fn dependencies(&self) -> CargoResult<BTreeMap<String, TomlDependency>> {
-        self.dependencies.clone().map_or(
-            Err(anyhow!("`workspace.dependencies` was not defined")),
-            |d| Ok(d),
-        )
+    /// Layers `other` on top of `self`: every field set in `other` wins, and
+    /// `dependencies` are merged by name with `other`'s entries winning.
+    /// The result uses `other`'s workspace root. The paths taken from `self`
+    /// (`readme`, `license-file` and path dependencies) keep resolving
+    /// against `self`'s root.
+    pub fn overlay(&self, other: &InheritableFields) -> InheritableFields {
+        let mut dependencies = self.dependencies.clone();
+        let mut dependency_roots = BTreeMap::new();
+        for name in self.dependencies.iter().flat_map(|deps| deps.keys()) {
+            dependency_roots.insert(name.clone(), self.dependency_root(name).to_path_buf());
+        }
+        for (name, dep) in other.dependencies.iter().flatten() {
+            dependencies
+                .get_or_insert_with(BTreeMap::new)
+                .insert(name.clone(), dep.clone());
+            match other.dependency_roots.get(name) {
+                Some(root) => dependency_roots.insert(name.clone(), root.clone()),
+                None => dependency_roots.remove(name),
+            };
+        }
+        let (readme, readme_root) = match &other.readme {
+            Some(_) => (other.readme.clone(), other.readme_root.clone()),
+            None => (
+                self.readme.clone(),
+                self.readme
+                    .as_ref()
+                    .map(|_| self.readme_root().to_path_buf()),
+            ),
+        };
+        let (license_file, license_file_root) = match &other.license_file {
+            Some(_) => (other.license_file.clone(), other.license_file_root.clone()),
+            None => (
+                self.license_file.clone(),
+                self.license_file
+                    .as_ref()
+                    .map(|_| self.license_file_root().to_path_buf()),
+            ),
+        };
+        InheritableFields {
+            dependencies,
+            version: other.version.clone().or_else(|| self.version.clone()),
+            authors: other.authors.clone().or_else(|| self.authors.clone()),
+            description: other
+                .description
+                .clone()
+                .or_else(|| self.description.clone()),
+            homepage: other.homepage.clone().or_else(|| self.homepage.clone()),
+            documentation: other
+                .documentation
+                .clone()
+                .or_else(|| self.documentation.clone()),
+            readme,
+            keywords: other.keywords.clone().or_else(|| self.keywords.clone()),
+            categories: other.categories.clone().or_else(|| self.categories.clone()),
+            license: other.license.clone().or_else(|| self.license.clone()),
+            license_file,
+            repository: other.repository.clone().or_else(|| self.repository.clone()),
+            publish: other.publish.clone().or_else(|| self.publish.clone()),
+            edition: other.edition.clone().or_else(|| self.edition.clone()),
+            badges: other.badges.clone().or_else(|| self.badges.clone()),
+            exclude: other.exclude.clone().or_else(|| self.exclude.clone()),
+            include: other.include.clone().or_else(|| self.include.clone()),
+            rust_version: other
+                .rust_version
+                .clone()
+                .or_else(|| self.rust_version.clone()),
+            features: other.features.clone().or_else(|| self.features.clone()),
+            ws_root: other.ws_root.clone(),
+            readme_root,
+            license_file_root,
+            dependency_roots,
+        }
     }
 
-    pub fn get_dependency(&self, name: &str) -> CargoResult<TomlDependency> {
-        self.dependencies.clone().map_or(
-            Err(anyhow!("`workspace.dependencies` was not defined")),
-            |deps| {
+    fn readme_root(&self) -> &Path {
+        self.readme_root.as_deref().unwrap_or(&self.ws_root)
+    }
+
+    fn license_file_root(&self) -> &Path {
+        self.license_file_root.as_deref().unwrap_or(&self.ws_root)
+    }
+
+    /// The directory that the `path` of the inherited dependency `name` is
+    /// relative to.
+    pub fn dependency_root(&self, name: &str) -> &Path {
+        self.dependency_roots.get(name).unwrap_or(&self.ws_root)
+    }
+
+    pub fn dependencies(&self) -> Result<BTreeMap<String, TomlDependency>, InheritanceError> {
+        self.dependencies
+            .clone()
+            .map_or(Err(InheritanceError::DependenciesNotDefined), |d| Ok(d))
+    }
+
+    pub fn get_dependency(&self, name: &str) -> Result<TomlDependency, InheritanceError> {
+        self.dependencies
+            .clone()
+            .map_or(Err(InheritanceError::DependenciesNotDefined), |deps| {
                 deps.get(name).map_or(
-                    Err(anyhow!(
-                        "`dependency.{}` was not found in `workspace.dependencies`",
-                        name
-                    )),
+                    Err(InheritanceError::DependencyNotFound(name.to_string())),
                     |dep| Ok(dep.clone()),
                 )
-            },
-        )
+            })
     }
 
-    pub fn version(&self) -> CargoResult<semver::Version> {
-        self.version.clone().map_or(
-            Err(anyhow!("`workspace.package.version` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn version(&self) -> Result<semver::Version, InheritanceError> {
+        self.version
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("version")), |d| Ok(d))
     }
 
-    pub fn authors(&self) -> CargoResult<Vec<String>> {
-        self.authors.clone().map_or(
-            Err(anyhow!("`workspace.package.authors` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn authors(&self) -> Result<Vec<String>, InheritanceError> {
+        self.authors
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("authors")), |d| Ok(d))
     }
 
-    pub fn description(&self) -> CargoResult<String> {
-        self.description.clone().map_or(
-            Err(anyhow!("`workspace.package.description` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn description(&self) -> Result<String, InheritanceError> {
+        self.description
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("description")), |d| {
+                Ok(d)
+            })
     }
 
-    pub fn homepage(&self) -> CargoResult<String> {
-        self.homepage.clone().map_or(
-            Err(anyhow!("`workspace.package.homepage` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn homepage(&self) -> Result<String, InheritanceError> {
+        self.homepage
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("homepage")), |d| {
+                Ok(d)
+            })
     }
 
-    pub fn documentation(&self) -> CargoResult<String> {
+    pub fn documentation(&self) -> Result<String, InheritanceError> {
         self.documentation.clone().map_or(
-            Err(anyhow!("`workspace.package.documentation` was not defined")),
+            Err(InheritanceError::FieldNotDefined("documentation")),
             |d| Ok(d),
         )
     }
 
-    pub fn readme(&self, package_root: &Path) -> CargoResult<StringOrBool> {
-        readme_for_project(self.ws_root.as_path(), self.readme.clone()).map_or(
-            Err(anyhow!("`workspace.package.readme` was not defined")),
+    pub fn readme(&self, package_root: &Path) -> Result<StringOrBool, InheritanceError> {
+        readme_for_project(self.readme_root(), self.readme.clone()).map_or(
+            Err(InheritanceError::FieldNotDefined("readme")),
             |readme| {
                 let rel_path =
-                    resolve_relative_path("readme", &self.ws_root, package_root, &readme)?;
+                    resolve_relative_path("readme", self.readme_root(), package_root, &readme)
+                        .map_err(InheritanceError::PathNotResolved)?;
                 Ok(StringOrBool::String(rel_path))
             },
         )
     }
 
-    pub fn keywords(&self) -> CargoResult<Vec<String>> {
-        self.keywords.clone().map_or(
-            Err(anyhow!("`workspace.package.keywords` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn keywords(&self) -> Result<Vec<String>, InheritanceError> {
+        self.keywords
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("keywords")), |d| {
+                Ok(d)
+            })
     }
 
-    pub fn categories(&self) -> CargoResult<Vec<String>> {
-        self.categories.clone().map_or(
-            Err(anyhow!("`workspace.package.categories` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn categories(&self) -> Result<Vec<String>, InheritanceError> {
+        self.categories
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("categories")), |d| {
+                Ok(d)
+            })
     }
 
-    pub fn license(&self) -> CargoResult<String> {
-        self.license.clone().map_or(
-            Err(anyhow!("`workspace.package.license` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn license(&self) -> Result<String, InheritanceError> {
+        self.license
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("license")), |d| Ok(d))
     }
 
-    pub fn license_file(&self, package_root: &Path) -> CargoResult<String> {
+    pub fn license_file(&self, package_root: &Path) -> Result<TomlLicenseFile, InheritanceError> {
         self.license_file.clone().map_or(
-            Err(anyhow!("`workspace.package.license_file` was not defined")),
-            |d| resolve_relative_path("license-file", &self.ws_root, package_root, &d),
+            Err(InheritanceError::FieldNotDefined("license-file")),
+            |d| {
+                d.try_map(|file| {
+                    resolve_relative_path(
+                        "license-file",
+                        self.license_file_root(),
+                        package_root,
+                        &file,
+                    )
+                })
+                .map_err(InheritanceError::PathNotResolved)
+            },
         )
     }
 
-    pub fn repository(&self) -> CargoResult<String> {
-        self.repository.clone().map_or(
-            Err(anyhow!("`workspace.package.repository` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn repository(&self) -> Result<String, InheritanceError> {
+        self.repository
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("repository")), |d| {
+                Ok(d)
+            })
     }
 
-    pub fn publish(&self) -> CargoResult<VecStringOrBool> {
-        self.publish.clone().map_or(
-            Err(anyhow!("`workspace.package.publish` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn publish(&self) -> Result<TomlPublishField, InheritanceError> {
+        self.publish
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("publish")), |d| Ok(d))
     }
 
-    pub fn edition(&self) -> CargoResult<String> {
-        self.edition.clone().map_or(
-            Err(anyhow!("`workspace.package.edition` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn edition(&self) -> Result<String, InheritanceError> {
+        self.edition
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("edition")), |d| Ok(d))
     }
 
-    pub fn rust_version(&self) -> CargoResult<String> {
+    pub fn rust_version(&self) -> Result<String, InheritanceError> {
         self.rust_version.clone().map_or(
-            Err(anyhow!("`workspace.package.rust-version` was not defined")),
+            Err(InheritanceError::FieldNotDefined("rust-version")),
             |d| Ok(d),
         )
     }
 
-    pub fn badges(&self) -> CargoResult<BTreeMap<String, BTreeMap<String, String>>> {
-        self.badges.clone().map_or(
-            Err(anyhow!("`workspace.package.badges` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn badges(&self) -> Result<BTreeMap<String, BTreeMap<String, String>>, InheritanceError> {
+        self.badges
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("badges")), |d| Ok(d))
     }
 
-    pub fn exclude(&self) -> CargoResult<Vec<String>> {
-        self.exclude.clone().map_or(
-            Err(anyhow!("`workspace.package.exclude` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn exclude(&self) -> Result<Vec<String>, InheritanceError> {
+        self.exclude
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("exclude")), |d| Ok(d))
     }
 
-    pub fn include(&self) -> CargoResult<Vec<String>> {
-        self.include.clone().map_or(
-            Err(anyhow!("`workspace.package.include` was not defined")),
-            |d| Ok(d),
-        )
+    pub fn include(&self) -> Result<Vec<String>, InheritanceError> {
+        self.include
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("include")), |d| Ok(d))
+    }
+
+    pub fn features(
+        &self,
+    ) -> Result<BTreeMap<InternedString, Vec<InternedString>>, InheritanceError> {
+        self.features
+            .clone()
+            .map_or(Err(InheritanceError::FieldNotDefined("features")), |d| {
+                Ok(d)
+            })
     }
 
     pub fn ws_root(&self) -> &PathBuf {
@@ -1308,6 +3147,22 @@ impl InheritableFields {
 }
 
 impl TomlProject {
+    /// The [`CompileKind`] of `package.default-target`, if set.
+    pub fn default_compile_kind(&self) -> CargoResult<Option<CompileKind>> {
+        self.default_target
+            .as_ref()
+            .map(|t| compile_kind_for_target("default-target", t))
+            .transpose()
+    }
+
+    /// The [`CompileKind`] of `package.forced-target`, if set.
+    pub fn forced_compile_kind(&self) -> CargoResult<Option<CompileKind>> {
+        self.forced_target
+            .as_ref()
+            .map(|t| compile_kind_for_target("forced-target", t))
+            .transpose()
+    }
+
     pub fn to_package_id(
         &self,
         source_id: SourceId,
@@ -1315,6 +3170,135 @@ impl TomlProject {
     ) -> CargoResult<PackageId> {
         PackageId::new(self.name, version, source_id)
     }
+
+    /// Resolves every `{ workspace = true }` field against `inheritable`,
+    /// collecting the errors instead of stopping at the first one.
+    ///
+    /// Fields that fail to resolve are left as they are. `readme` and
+    /// `license-file` are only checked for being defined in the workspace,
+    /// since rewriting their paths needs the member's package root.
+    pub fn resolve_all_inheritance(
+        &self,
+        inheritable: &InheritableFields,
+        features: &Features,
+    ) -> (TomlProject, Vec<anyhow::Error>) {
+        fn resolve<T: InheritableValue + Clone>(
+            field: Option<&mut MaybeWorkspace<T>>,
+            label: &str,
+            inheritable: &InheritableFields,
+            features: &Features,
+            errors: &mut Vec<anyhow::Error>,
+        ) {
+            if let Some(field @ MaybeWorkspace::Workspace(_)) = field {
+                match resolve_field(field.clone(), inheritable, features, label) {
+                    Ok(value) => *field = MaybeWorkspace::Defined(value),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+
+        let mut project = self.clone();
+        let mut errors = Vec::new();
+
+        if let Some(edition @ MaybeWorkspace::Workspace(_)) = &mut project.edition {
+            match resolve_edition(edition.clone(), features, || inheritable.edition()) {
+                Ok(resolved) => *edition = MaybeWorkspace::Defined(resolved.to_string()),
+                Err(e) => errors.push(e),
+            }
+        }
+        macro_rules! resolve_fields {
+            ($($field:ident => $label:literal),*) => {$(
+                resolve(project.$field.as_mut(), $label, inheritable, features, &mut errors);
+            )*};
+        }
+        resolve_fields!(
+            rust_version => "rust-version",
+            version => "version",
+            authors => "authors",
+            exclude => "exclude",
+            include => "include",
+            publish => "publish",
+            description => "description",
+            homepage => "homepage",
+            documentation => "documentation",
+            keywords => "keywords",
+            categories => "categories",
+            license => "license",
+            repository => "repository"
+        );
+        for (label, inherits, defined) in [
+            (
+                "readme",
+                project
+                    .readme
+                    .as_ref()
+                    .map_or(false, MaybeWorkspace::is_workspace),
+                inheritable.readme.is_some(),
+            ),
+            (
+                "license-file",
+                project
+                    .license_file
+                    .as_ref()
+                    .map_or(false, MaybeWorkspace::is_workspace),
+                inheritable.license_file.is_some(),
+            ),
+        ] {
+            if !inherits {
+                continue;
+            }
+            if let Err(err) = features.require(Feature::workspace_inheritance()) {
+                errors.push(err);
+            } else if !defined {
+                errors.push(anyhow!(
+                    "error inheriting `{}` from workspace root manifest's `workspace.package.{}`: {}",
+                    label,
+                    label,
+                    InheritanceError::FieldNotDefined(label)
+                ));
+            }
+        }
+
+        (project, errors)
+    }
+
+    /// Returns the names of the fields that are still set to `{ workspace = true }`.
+    ///
+    /// This is useful for detecting a package manifest that cannot stand alone
+    /// without its workspace root, before any inheritance has been resolved.
+    pub fn unresolved_workspace_fields(&self) -> Vec<&'static str> {
+        fn is_workspace<T>(field: Option<&MaybeWorkspace<T>>) -> bool {
+            field.map_or(false, MaybeWorkspace::is_workspace)
+        }
+
+        [
+            ("edition", is_workspace(self.edition.as_ref())),
+            ("rust-version", is_workspace(self.rust_version.as_ref())),
+            ("version", is_workspace(self.version.as_ref())),
+            ("authors", is_workspace(self.authors.as_ref())),
+            ("exclude", is_workspace(self.exclude.as_ref())),
+            ("include", is_workspace(self.include.as_ref())),
+            ("publish", is_workspace(self.publish.as_ref())),
+            ("description", is_workspace(self.description.as_ref())),
+            ("homepage", is_workspace(self.homepage.as_ref())),
+            ("documentation", is_workspace(self.documentation.as_ref())),
+            ("readme", is_workspace(self.readme.as_ref())),
+            ("keywords", is_workspace(self.keywords.as_ref())),
+            ("categories", is_workspace(self.categories.as_ref())),
+            ("license", is_workspace(self.license.as_ref())),
+            ("license-file", is_workspace(self.license_file.as_ref())),
+            ("repository", is_workspace(self.repository.as_ref())),
+        ]
+        .into_iter()
+        .filter(|(_, unresolved)| *unresolved)
+        .map(|(name, _)| name)
+        .collect()
+    }
+
+    /// Returns the `[package.metadata]` table, if any.
+    pub fn custom_metadata(&self) -> Option<&toml::Value> {
+        self.metadata.as_ref()
+    }
 }
 
 struct Context<'a, 'b> {
@@ -1337,11 +3321,16 @@ impl TomlManifest {
         ws: &Workspace<'_>,
         package_root: &Path,
     ) -> CargoResult<TomlManifest> {
+        // The deprecated `snake_case` aliases were already warned about when
+        // the manifest was loaded, so collapse them quietly here to publish
+        // only the `kebab-case` keys.
+        let mut me = self.clone();
+        me.normalize_aliases(&mut Vec::new());
         let config = ws.config();
-        let mut package = self
+        let mut package = me
             .package
             .as_ref()
-            .or_else(|| self.project.as_ref())
+            .or_else(|| me.project.as_ref())
             .unwrap()
             .clone();
         package.workspace = None;
@@ -1350,20 +3339,31 @@ impl TomlManifest {
             let license_file = license_file
                 .as_defined()
                 .context("license file should have been resolved before `prepare_for_publish()`")?;
-            let license_path = Path::new(&license_file);
-            let abs_license_path = paths::normalize_path(&package_root.join(license_path));
-            if abs_license_path.strip_prefix(package_root).is_err() {
-                // This path points outside of the package root. `cargo package`
-                // will copy it into the root, so adjust the path to this location.
-                package.license_file = Some(MaybeWorkspace::Defined(
+            let license_file = license_file.clone().try_map(|license_file| {
+                let license_path = Path::new(&license_file);
+                let abs_license_path = paths::normalize_path(&package_root.join(license_path));
+                Ok(if abs_license_path.strip_prefix(package_root).is_err() {
+                    // This path points outside of the package root. `cargo package`
+                    // will copy it into the root, so adjust the path to this location.
                     license_path
                         .file_name()
                         .unwrap()
                         .to_str()
                         .unwrap()
-                        .to_string(),
-                ));
-            }
+                        .to_string()
+                } else {
+                    license_file
+                })
+            })?;
+            // Versions of Cargo without `multiple-license-files` only read a
+            // single string.
+            let license_file = match license_file {
+                TomlLicenseFile::Many(mut files) if files.len() == 1 => {
+                    TomlLicenseFile::One(files.remove(0))
+                }
+                license_file => license_file,
+            };
+            package.license_file = Some(MaybeWorkspace::Defined(license_file));
         }
 
         if let Some(readme) = &package.readme {
@@ -1394,31 +3394,23 @@ impl TomlManifest {
         return Ok(TomlManifest {
             package: Some(package),
             project: None,
-            profile: self.profile.clone(),
-            lib: self.lib.clone(),
-            bin: self.bin.clone(),
-            example: self.example.clone(),
-            test: self.test.clone(),
-            bench: self.bench.clone(),
-            dependencies: map_deps(config, self.dependencies.as_ref(), all)?,
-            dev_dependencies: map_deps(
-                config,
-                self.dev_dependencies
-                    .as_ref()
-                    .or_else(|| self.dev_dependencies2.as_ref()),
-                TomlDependency::is_version_specified,
-            )?,
-            dev_dependencies2: None,
-            build_dependencies: map_deps(
+            profile: me.profile.clone(),
+            lib: me.lib.clone(),
+            bin: me.bin.clone(),
+            example: me.example.clone(),
+            test: me.test.clone(),
+            bench: me.bench.clone(),
+            dependencies: map_deps(config, me.dependencies.as_ref(), all)?,
+            dev_dependencies: map_deps(
                 config,
-                self.build_dependencies
-                    .as_ref()
-                    .or_else(|| self.build_dependencies2.as_ref()),
-                all,
+                me.dev_dependencies.as_ref(),
+                TomlDependency::is_version_specified,
             )?,
+            dev_dependencies2: None,
+            build_dependencies: map_deps(config, me.build_dependencies.as_ref(), all)?,
             build_dependencies2: None,
-            features: self.features.clone(),
-            target: match self.target.as_ref().map(|target_map| {
+            features: me.features.clone(),
+            target: match me.target.as_ref().map(|target_map| {
                 target_map
                     .iter()
                     .map(|(k, v)| {
@@ -1428,23 +3420,28 @@ impl TomlManifest {
                                 dependencies: map_deps(config, v.dependencies.as_ref(), all)?,
                                 dev_dependencies: map_deps(
                                     config,
-                                    v.dev_dependencies
-                                        .as_ref()
-                                        .or_else(|| v.dev_dependencies2.as_ref()),
+                                    v.dev_dependencies.as_ref(),
                                     TomlDependency::is_version_specified,
                                 )?,
                                 dev_dependencies2: None,
                                 build_dependencies: map_deps(
                                     config,
-                                    v.build_dependencies
-                                        .as_ref()
-                                        .or_else(|| v.build_dependencies2.as_ref()),
+                                    v.build_dependencies.as_ref(),
                                     all,
                                 )?,
                                 build_dependencies2: None,
                             },
                         ))
                     })
+                    // A platform whose dependencies were all dropped above is
+                    // left out entirely.
+                    .filter(|platform| {
+                        platform.as_ref().map_or(true, |(_, p)| {
+                            p.dependencies.is_some()
+                                || p.dev_dependencies.is_some()
+                                || p.build_dependencies.is_some()
+                        })
+                    })
                     .collect()
             }) {
                 Some(Ok(v)) => Some(v),
@@ -1454,8 +3451,8 @@ impl TomlManifest {
             replace: None,
             patch: None,
             workspace: None,
-            badges: self.badges.clone(),
-            cargo_features: self.cargo_features.clone(),
+            badges: me.badges.clone(),
+            cargo_features: me.cargo_features.clone(),
         });
 
         fn map_deps(
@@ -1472,7 +3469,9 @@ impl TomlManifest {
                 .filter(|(_k, v)| filter(v))
                 .map(|(k, v)| Ok((k.clone(), map_dependency(config, v)?)))
                 .collect::<CargoResult<BTreeMap<_, _>>>()?;
-            Ok(Some(deps))
+            // Don't publish a table that is empty, for example because it
+            // only had dev-dependencies without a version.
+            Ok(Some(deps).filter(|deps| !deps.is_empty()))
         }
 
         fn map_dependency(config: &Config, dep: &TomlDependency) -> CargoResult<TomlDependency> {
@@ -1503,11 +3502,42 @@ impl TomlManifest {
         }
     }
 
+    /// Runs [`TomlManifest::prepare_for_publish`] and serializes the result,
+    /// i.e. the `Cargo.toml` that ends up in the published `.crate` file, minus
+    /// the explanatory preamble.
+    pub fn to_published_toml_string(
+        &self,
+        ws: &Workspace<'_>,
+        package_root: &Path,
+    ) -> CargoResult<String> {
+        let manifest = self.prepare_for_publish(ws, package_root)?;
+        Ok(toml::to_string_pretty(&manifest)?)
+    }
+
     pub fn to_real_manifest(
         me: &Rc<TomlManifest>,
         source_id: SourceId,
         package_root: &Path,
         config: &Config,
+    ) -> CargoResult<(Manifest, Vec<PathBuf>)> {
+        TomlManifest::to_real_manifest_in_memory(me, source_id, package_root, config, &|path| {
+            path.is_file()
+        })
+    }
+
+    /// Like [`TomlManifest::to_real_manifest`], but asks `is_file` whether a
+    /// file exists instead of looking on disk.
+    ///
+    /// This covers inferring `build.rs` and the default README. Target
+    /// auto-discovery and workspace inheritance still read the filesystem,
+    /// so a manifest without files on disk should declare its targets and
+    /// not inherit from a workspace.
+    pub fn to_real_manifest_in_memory(
+        me: &Rc<TomlManifest>,
+        source_id: SourceId,
+        package_root: &Path,
+        config: &Config,
+        is_file: &dyn Fn(&Path) -> bool,
     ) -> CargoResult<(Manifest, Vec<PathBuf>)> {
         fn get_ws(
             config: &Config,
@@ -1541,9 +3571,8 @@ impl TomlManifest {
         let mut errors = vec![];
 
         // Parse features first so they will be available when parsing other parts of the TOML.
-        let empty = Vec::new();
-        let cargo_features = me.cargo_features.as_ref().unwrap_or(&empty);
-        let features = Features::new(cargo_features, config, &mut warnings, source_id.is_path())?;
+        let cargo_features = dedup_cargo_features(me.declared_cargo_features(), &mut warnings);
+        let features = Features::new(&cargo_features, config, &mut warnings, source_id.is_path())?;
 
         let project = me.project.clone().or_else(|| me.package.clone());
         let project = &mut project.ok_or_else(|| anyhow!("no `package` section found"))?;
@@ -1561,6 +3590,13 @@ impl TomlManifest {
                     &Some(inheritable),
                     &toml_config.metadata,
                 );
+                for glob in ws_root_config.unmatched_member_globs()? {
+                    warnings.push(format!(
+                        "workspace member pattern `{}` in `workspace.members` \
+                         does not match any directory",
+                        glob
+                    ));
+                }
                 config
                     .ws_roots
                     .borrow_mut()
@@ -1586,55 +3622,92 @@ impl TomlManifest {
         let resolved_path = package_root.join("Cargo.toml");
 
         let inherit_cell: LazyCell<InheritableFields> = LazyCell::new();
-        let inherit =
-            || inherit_cell.try_borrow_with(|| get_ws(config, &resolved_path, &workspace_config));
+        let inherit = || {
+            inherit_cell
+                .try_borrow_with(|| get_ws(config, &resolved_path, &workspace_config))
+                .map_err(InheritanceError::WorkspaceNotLoaded)
+        };
 
-        let version = project
-            .version
-            .clone()
-            .resolve(&features, "version", || inherit()?.version())?;
+        if project.version.is_none() && features.is_enabled(Feature::implicit_version_inheritance())
+        {
+            // As with `rust-version` below, a package outside of a workspace
+            // falls through to the "missing version" error instead.
+            if let Some(version) = inherit().ok().and_then(|ws| ws.version.clone()) {
+                project.version = Some(MaybeWorkspace::Defined(version));
+            }
+        }
 
-        project.version = MaybeWorkspace::Defined(version.clone());
+        let version = match project.version.clone() {
+            Some(version) => version.resolve(&features, "version", || inherit()?.version())?,
+            None if features.is_enabled(Feature::implicit_version_inheritance()) => bail!(
+                "`package.version` is required, and there is no \
+                 `workspace.package.version` to inherit it from"
+            ),
+            None => bail!("`package.version` is required"),
+        };
+
+        project.version = Some(MaybeWorkspace::Defined(version.clone()));
 
         let pkgid = project.to_package_id(source_id, version)?;
 
         let edition = if let Some(edition) = project.edition.clone() {
-            let edition: Edition = edition
-                .resolve(&features, "edition", || inherit()?.edition())?
-                .parse()
-                .with_context(|| "failed to parse the `edition` key")?;
+            let edition = resolve_edition(edition, &features, || inherit()?.edition())?;
             project.edition = Some(MaybeWorkspace::Defined(edition.to_string()));
             edition
         } else {
             Edition::Edition2015
         };
-        // Add these lines if start a new unstable edition.
-        // ```
-        // if edition == Edition::Edition20xx {
-        //     features.require(Feature::edition20xx))?;
-        // }
-        // ```
-        if !edition.is_stable() {
-            // Guard in case someone forgets to add .require()
-            return Err(util::errors::internal(format!(
-                "edition {} should be gated",
-                edition
-            )));
-        }
-
-        let rust_version = if let Some(rust_version) = &project.rust_version {
+        gate_edition(edition, &features)?;
+
+        if let Some(TomlBuild::Multiple(scripts)) = &project.build {
+            features.require(Feature::multiple_build_scripts())?;
+            validate_build_scripts(scripts)?;
+        }
+
+        if project.rust_version.is_none()
+            && features.is_enabled(Feature::implicit_rust_version_inheritance())
+        {
+            // A package outside of a workspace has nothing to inherit, so a
+            // workspace root that can't be found is not an error here.
+            if let Some(rust_version) = inherit().ok().and_then(|ws| ws.rust_version.clone()) {
+                project.rust_version = Some(MaybeWorkspace::Defined(rust_version));
+            }
+        }
+
+        let (rust_version, rust_version_req) = if let Some(rust_version) = &project.rust_version {
             let rust_version = rust_version
                 .clone()
                 .resolve(&features, "rust_version", || inherit()?.rust_version())?;
+            let is_bare = rust_version.chars().all(|c| c.is_ascii_digit() || c == '.');
             let req = match semver::VersionReq::parse(&rust_version) {
                 // Exclude semver operators like `^` and pre-release identifiers
-                Ok(req) if rust_version.chars().all(|c| c.is_ascii_digit() || c == '.') => req,
-                _ => bail!("`rust-version` must be a value like \"1.32\""),
+                Ok(req) if is_bare => req,
+                Ok(req)
+                    if features.is_enabled(Feature::rust_version_ranges())
+                        && req.comparators.iter().all(|c| c.pre.is_empty()) =>
+                {
+                    req
+                }
+                Err(e) if !is_bare && features.is_enabled(Feature::rust_version_ranges()) => {
+                    bail!(
+                        "`rust-version` of \"{}\" is not a valid version requirement: {}",
+                        rust_version,
+                        e
+                    )
+                }
+                _ => match rust_version.parse::<semver::Version>() {
+                    Ok(v) if !v.pre.is_empty() || !v.build.is_empty() => bail!(
+                        "`rust-version` of \"{}\" is not allowed to contain pre-release \
+                         or build metadata identifiers, use a value like \"{}.{}\"",
+                        rust_version,
+                        v.major,
+                        v.minor,
+                    ),
+                    _ => bail!("`rust-version` must be a value like \"1.32\""),
+                },
             };
             if let Some(first_version) = edition.first_version() {
-                let unsupported =
-                    semver::Version::new(first_version.major, first_version.minor - 1, 9999);
-                if req.matches(&unsupported) {
+                if rust_version_lower_bound(&req) < first_version {
                     bail!(
                         "rust-version {} is older than first version ({}) required by \
                             the specified edition ({})",
@@ -1644,9 +3717,9 @@ impl TomlManifest {
                     )
                 }
             }
-            Some(rust_version.clone())
+            (Some(rust_version.clone()), Some(req))
         } else {
-            None
+            (None, None)
         };
 
         if project.metabuild.is_some() {
@@ -1675,6 +3748,7 @@ impl TomlManifest {
             edition,
             &project.build,
             &project.metabuild,
+            is_file,
             &mut warnings,
             &mut errors,
         )?;
@@ -1683,6 +3757,12 @@ impl TomlManifest {
             debug!("manifest has no build targets");
         }
 
+        let build_scripts = me
+            .maybe_custom_build(&project.build, package_root, is_file)
+            .into_iter()
+            .map(|script| package_root.join(script))
+            .collect();
+
         if let Err(e) = unique_build_targets(&targets, package_root) {
             warnings.push(format!(
                 "file found to be present in multiple \
@@ -1692,6 +3772,24 @@ impl TomlManifest {
         }
 
         if let Some(links) = &project.links {
+            // Packages from other sources may have been published before
+            // these checks existed, so only local packages are held to them.
+            if source_id.is_path() {
+                if links.is_empty() {
+                    bail!("package `{}` has an empty `links` value", pkgid)
+                }
+                if let Some(ch) = links
+                    .chars()
+                    .find(|ch| !(ch.is_alphanumeric() || matches!(ch, '_' | '-')))
+                {
+                    bail!(
+                        "invalid character `{}` in `links` value `{}`, \
+                         characters must be letters, numbers, `-`, or `_`",
+                        ch,
+                        links
+                    )
+                }
+            }
             if !targets.iter().any(|t| t.is_custom_build()) {
                 bail!(
                     "package `{}` specifies that it links to `{}` but does not \
@@ -1737,8 +3835,11 @@ impl TomlManifest {
             let mut deps: BTreeMap<String, TomlDependency> = BTreeMap::new();
             for (n, v) in dependencies.iter() {
                 let resolved = v.clone().resolve(features, n, cx, || inherit())?;
+                validate_dependency_name(n, resolved.package())?;
+                if kind.is_none() && features.is_enabled(Feature::lint_exact_version_pins()) {
+                    warn_on_exact_pin(n, &resolved, cx.warnings);
+                }
                 let dep = resolved.to_dependency(n, cx, kind)?;
-                validate_package_name(dep.name_in_toml().as_str(), "dependency name", "")?;
                 cx.deps.push(dep);
                 deps.insert(n.to_string(), resolved.clone());
             }
@@ -1757,14 +3858,10 @@ impl TomlManifest {
         if me.dev_dependencies.is_some() && me.dev_dependencies2.is_some() {
             warn_on_deprecated("dev-dependencies", package_name, "package", cx.warnings);
         }
-        let dev_deps = me
-            .dev_dependencies
-            .as_ref()
-            .or_else(|| me.dev_dependencies2.as_ref());
         let dev_deps = process_dependencies(
             &features,
             &mut cx,
-            dev_deps,
+            me.dependency_table(Some(DepKind::Development)),
             Some(DepKind::Development),
             &workspace_config,
             &inherit_cell,
@@ -1772,14 +3869,10 @@ impl TomlManifest {
         if me.build_dependencies.is_some() && me.build_dependencies2.is_some() {
             warn_on_deprecated("build-dependencies", package_name, "package", cx.warnings);
         }
-        let build_deps = me
-            .build_dependencies
-            .as_ref()
-            .or_else(|| me.build_dependencies2.as_ref());
         let build_deps = process_dependencies(
             &features,
             &mut cx,
-            build_deps,
+            me.dependency_table(Some(DepKind::Build)),
             Some(DepKind::Build),
             &workspace_config,
             &inherit_cell,
@@ -1881,16 +3974,76 @@ impl TomlManifest {
             .map(|mw| mw.resolve(&features, "include", || inherit()?.include()))
             .transpose()?
             .unwrap_or_default();
+        if !exclude.is_empty() && !include.is_empty() {
+            warnings.push(
+                "both package.include and package.exclude are specified; \
+                 the exclude list will be ignored"
+                    .to_string(),
+            );
+        }
+        for (key, patterns) in [("include", &include), ("exclude", &exclude)] {
+            // These are compiled the same way when listing the package's files.
+            let mut builder = GitignoreBuilder::new(package_root);
+            for pattern in patterns {
+                if let Err(e) = builder.add_line(None, pattern) {
+                    warnings.push(format!(
+                        "invalid pattern `{}` in `package.{}`: {}",
+                        pattern, key, e
+                    ));
+                }
+            }
+        }
+        let resolved_features = me
+            .features
+            .clone()
+            .map(|f| f.resolve(&features, || inherit()?.features()))
+            .transpose()?;
+        if let Some(resolved_features) = &resolved_features {
+            lint_features_shadowing_deps(resolved_features, &deps, &mut warnings);
+        }
         let empty_features = BTreeMap::new();
 
         let summary = Summary::new(
             config,
             pkgid,
             deps,
-            me.features.as_ref().unwrap_or(&empty_features),
+            resolved_features.as_ref().unwrap_or(&empty_features),
             project.links.as_deref(),
         )?;
 
+        // Deduplicate after inheritance so that duplicates coming from the
+        // workspace are caught as well.
+        let license_file = project
+            .license_file
+            .clone()
+            .map(|mw| {
+                mw.resolve(&features, "license-file", || {
+                    inherit()?.license_file(package_root)
+                })
+            })
+            .transpose()?;
+        if let Some(TomlLicenseFile::Many(_)) = license_file {
+            features.require(Feature::multiple_license_files())?;
+        }
+
+        let mut authors: Vec<String> = Vec::new();
+        for author in project
+            .authors
+            .clone()
+            .map(|mw| mw.resolve(&features, "authors", || inherit()?.authors()))
+            .transpose()?
+            .unwrap_or_default()
+        {
+            if authors.contains(&author) {
+                warnings.push(format!(
+                    "duplicate author `{}` in `package.authors` is ignored",
+                    author
+                ));
+            } else {
+                authors.push(author);
+            }
+        }
+
         let metadata = ManifestMetadata {
             description: project
                 .description
@@ -1907,34 +4060,24 @@ impl TomlManifest {
                 .clone()
                 .map(|mw| mw.resolve(&features, "documentation", || inherit()?.documentation()))
                 .transpose()?,
-            readme: readme_for_project(
+            readme: readme_for_project_with(
                 package_root,
                 project
                     .readme
                     .clone()
                     .map(|mw| mw.resolve(&features, "readme", || inherit()?.readme(package_root)))
                     .transpose()?,
+                is_file,
             ),
-            authors: project
-                .authors
-                .clone()
-                .map(|mw| mw.resolve(&features, "authors", || inherit()?.authors()))
-                .transpose()?
-                .unwrap_or_default(),
+            authors,
             license: project
                 .license
                 .clone()
                 .map(|mw| mw.resolve(&features, "license", || inherit()?.license()))
                 .transpose()?,
-            license_file: project
-                .license_file
-                .clone()
-                .map(|mw| {
-                    mw.resolve(&features, "license", || {
-                        inherit()?.license_file(package_root)
-                    })
-                })
-                .transpose()?,
+            license_file: license_file
+                .as_ref()
+                .map_or_else(Vec::new, |license_file| license_file.files().to_vec()),
             repository: project
                 .repository
                 .clone()
@@ -1960,6 +4103,53 @@ impl TomlManifest {
                 .unwrap_or_default(),
             links: project.links.clone(),
         };
+        validate_keywords(&metadata.keywords)?;
+        validate_categories(&metadata.categories)?;
+        if features.is_enabled(Feature::lint_metadata_urls()) {
+            for (field, value) in [
+                ("homepage", &metadata.homepage),
+                ("documentation", &metadata.documentation),
+                ("repository", &metadata.repository),
+            ] {
+                if let Some(value) = value {
+                    if !matches!(Url::parse(value), Ok(url) if !url.cannot_be_a_base()) {
+                        warnings.push(format!(
+                            "`package.{}` value `{}` is not an absolute URL, \
+                             consider adding a scheme such as `https://`",
+                            field, value
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some(maintenance) = metadata.badges.get("maintenance") {
+            const STATUSES: [&str; 7] = [
+                "actively-developed",
+                "passively-maintained",
+                "as-is",
+                "experimental",
+                "looking-for-maintainer",
+                "deprecated",
+                "none",
+            ];
+            let status = maintenance.get("status").map(String::as_str);
+            if !status.map_or(false, |status| STATUSES.contains(&status)) {
+                let found = match status {
+                    Some(status) => format!("is `{}`", status),
+                    None => "is missing".to_string(),
+                };
+                warnings.push(format!(
+                    "`badges.maintenance.status` {}, expected one of {}",
+                    found,
+                    STATUSES
+                        .iter()
+                        .map(|s| format!("`{}`", s))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+
         project.description = metadata
             .description
             .clone()
@@ -1984,10 +4174,7 @@ impl TomlManifest {
             .license
             .clone()
             .map(|license| MaybeWorkspace::Defined(license));
-        project.license_file = metadata
-            .license_file
-            .clone()
-            .map(|license_file| MaybeWorkspace::Defined(license_file));
+        project.license_file = license_file.map(MaybeWorkspace::Defined);
         project.repository = metadata
             .repository
             .clone()
@@ -2021,7 +4208,28 @@ impl TomlManifest {
                 .unwrap()
         });
 
-        project.publish = publish.clone().map(|p| MaybeWorkspace::Defined(p));
+        let (publish, publish_reason) = match publish {
+            Some(TomlPublishField::Detailed(detailed)) => {
+                features.require(Feature::publish_reason())?;
+                if detailed.registry && detailed.reason.is_some() {
+                    bail!(
+                        "`package.publish.reason` is only allowed when `registry = false`, \
+                         as the package can be published"
+                    );
+                }
+                (
+                    Some(VecStringOrBool::Bool(detailed.registry)),
+                    detailed.reason,
+                )
+            }
+            Some(TomlPublishField::Plain(plain)) => (Some(plain), None),
+            None => (None, None),
+        };
+        // The reason only matters to this Cargo; keep the resolved manifest
+        // in the plain form so older versions can still read it.
+        project.publish = publish
+            .clone()
+            .map(|p| MaybeWorkspace::Defined(TomlPublishField::Plain(p)));
 
         let publish = match publish {
             Some(VecStringOrBool::VecString(ref vecstring)) => Some(vecstring.clone()),
@@ -2029,6 +4237,31 @@ impl TomlManifest {
             None | Some(VecStringOrBool::Bool(true)) => None,
         };
 
+        if rust_version.is_some()
+            && publish.as_ref().map_or(false, |p| p.is_empty())
+            && features.is_enabled(Feature::lint_unpublished_rust_version())
+        {
+            warnings.push(
+                "`rust-version` is set, but the package is not published \
+                 (`publish = false`), so the field may be unnecessary"
+                    .to_string(),
+            );
+        }
+
+        if publish.as_ref().map_or(true, |p| !p.is_empty())
+            && features.is_enabled(Feature::lint_author_emails())
+        {
+            for author in &metadata.authors {
+                if !is_valid_author(author) {
+                    warnings.push(format!(
+                        "`package.authors` entry `{}` should be a name, optionally followed \
+                         by an email address in angle brackets, like `Jane Doe <jane@example.com>`",
+                        author
+                    ));
+                }
+            }
+        }
+
         if summary.features().contains_key("default-features") {
             warnings.push(
                 "`default-features = [\"..\"]` was found in [features]. \
@@ -2049,18 +4282,8 @@ impl TomlManifest {
             }
         }
 
-        let default_kind = project
-            .default_target
-            .as_ref()
-            .map(|t| CompileTarget::new(&*t))
-            .transpose()?
-            .map(CompileKind::Target);
-        let forced_kind = project
-            .forced_target
-            .as_ref()
-            .map(|t| CompileTarget::new(&*t))
-            .transpose()?
-            .map(CompileKind::Target);
+        let default_kind = project.default_compile_kind()?;
+        let forced_kind = project.forced_compile_kind()?;
         let custom_metadata = project.metadata.clone();
         let resolved_toml = TomlManifest {
             cargo_features: me.cargo_features.clone(),
@@ -2077,7 +4300,7 @@ impl TomlManifest {
             dev_dependencies2: None,
             build_dependencies: build_deps,
             build_dependencies2: None,
-            features: me.features.clone(),
+            features: resolved_features.map(TomlFeatures::from),
             target,
             replace: me.replace.clone(),
             patch: me.patch.clone(),
@@ -2099,16 +4322,19 @@ impl TomlManifest {
             custom_metadata,
             profiles,
             publish,
+            publish_reason,
             replace,
             patch,
             workspace_config,
             features,
             edition,
             rust_version,
+            rust_version_req,
             project.im_a_teapot,
             project.default_run.clone(),
             Rc::new(resolved_toml),
             project.metabuild.clone().map(|sov| sov.0),
+            build_scripts,
             resolve_behavior,
         );
         if project.license_file.is_some() && project.license.is_some() {
@@ -2183,9 +4409,8 @@ impl TomlManifest {
         let mut nested_paths = Vec::new();
         let mut warnings = Vec::new();
         let mut deps = Vec::new();
-        let empty = Vec::new();
-        let cargo_features = me.cargo_features.as_ref().unwrap_or(&empty);
-        let features = Features::new(cargo_features, config, &mut warnings, source_id.is_path())?;
+        let cargo_features = dedup_cargo_features(me.declared_cargo_features(), &mut warnings);
+        let features = Features::new(&cargo_features, config, &mut warnings, source_id.is_path())?;
 
         let (replace, patch) = {
             let mut cx = Context {
@@ -2204,12 +4429,10 @@ impl TomlManifest {
         if let Some(profiles) = &profiles {
             profiles.validate(&features, &mut warnings)?;
         }
-        let resolve_behavior = me
-            .workspace
-            .as_ref()
-            .and_then(|ws| ws.resolver.as_deref())
-            .map(|r| ResolveBehavior::from_manifest(r))
-            .transpose()?;
+        let resolve_behavior = match &me.workspace {
+            Some(ws) => ws.resolve_behavior()?,
+            None => None,
+        };
         let workspace_config = match me.workspace {
             Some(ref toml_config) => {
                 let mut inheritable = toml_config.package.clone().unwrap_or_default();
@@ -2223,6 +4446,13 @@ impl TomlManifest {
                     &Some(inheritable),
                     &toml_config.metadata,
                 );
+                for glob in ws_root_config.unmatched_member_globs()? {
+                    warnings.push(format!(
+                        "workspace member pattern `{}` in `workspace.members` \
+                         does not match any directory",
+                        glob
+                    ));
+                }
                 config
                     .ws_roots
                     .borrow_mut()
@@ -2233,17 +4463,18 @@ impl TomlManifest {
                 bail!("virtual manifests must be configured with [workspace]");
             }
         };
-        Ok((
-            VirtualManifest::new(
-                replace,
-                patch,
-                workspace_config,
-                profiles,
-                features,
-                resolve_behavior,
-            ),
-            nested_paths,
-        ))
+        let mut manifest = VirtualManifest::new(
+            replace,
+            patch,
+            workspace_config,
+            profiles,
+            features,
+            resolve_behavior,
+        );
+        for warning in warnings {
+            manifest.warnings_mut().add_warning(warning);
+        }
+        Ok((manifest, nested_paths))
     }
 
     fn replace(&self, cx: &mut Context<'_, '_>) -> CargoResult<Vec<(PackageIdSpec, Dependency)>> {
@@ -2291,6 +4522,19 @@ impl TomlManifest {
         for (url, deps) in self.patch.iter().flatten() {
             let url = match &url[..] {
                 CRATES_IO_REGISTRY => CRATES_IO_INDEX.parse().unwrap(),
+                // A bare registry name can never be parsed as a URL, so point
+                // at the missing registry instead of at the URL parse error.
+                _ if validate_package_name(url, "registry name", "").is_ok()
+                    && cx
+                        .config
+                        .get_string(&format!("registries.{}.index", url))?
+                        .is_none() =>
+                {
+                    bail!(
+                        "registry `{0}` is not configured; add it under [registries.{0}]",
+                        url
+                    )
+                }
                 _ => cx
                     .config
                     .get_registry_index(url)
@@ -2309,37 +4553,332 @@ impl TomlManifest {
         Ok(patch)
     }
 
-    /// Returns the path to the build script if one exists for this crate.
+    /// Returns the paths to the build scripts of this crate, in the order
+    /// they are declared.
     fn maybe_custom_build(
         &self,
-        build: &Option<StringOrBool>,
+        build: &Option<TomlBuild>,
         package_root: &Path,
-    ) -> Option<PathBuf> {
+        is_file: &dyn Fn(&Path) -> bool,
+    ) -> Vec<PathBuf> {
         let build_rs = package_root.join("build.rs");
         match *build {
             // Explicitly no build script.
-            Some(StringOrBool::Bool(false)) => None,
-            Some(StringOrBool::Bool(true)) => Some(build_rs),
-            Some(StringOrBool::String(ref s)) => Some(PathBuf::from(s)),
+            Some(TomlBuild::Bool(false)) => Vec::new(),
+            Some(TomlBuild::Bool(true)) => vec![build_rs],
+            Some(TomlBuild::String(ref s)) => vec![PathBuf::from(s)],
+            Some(TomlBuild::Multiple(ref scripts)) => scripts.iter().map(PathBuf::from).collect(),
             None => {
                 // If there is a `build.rs` file next to the `Cargo.toml`, assume it is
                 // a build script.
-                if build_rs.is_file() {
-                    Some(build_rs)
+                if is_file(&build_rs) {
+                    vec![build_rs]
                 } else {
-                    None
+                    Vec::new()
                 }
             }
         }
     }
 
+    /// Returns the `cargo-features` in the order they were declared,
+    /// including any duplicates.
+    pub fn declared_cargo_features(&self) -> &[String] {
+        self.cargo_features.as_deref().unwrap_or_default()
+    }
+
     pub fn has_profiles(&self) -> bool {
         self.profile.is_some()
     }
 
+    /// Returns the top-level dependency table for `kind`, where `None` means
+    /// the normal `[dependencies]`. The deprecated `dev_dependencies` and
+    /// `build_dependencies` spellings are used when the `kebab-case` table
+    /// is absent.
+    pub fn dependencies_for_kind(&self, kind: Option<DepKind>) -> BTreeMap<&str, &TomlDependency> {
+        self.dependency_table(kind)
+            .into_iter()
+            .flatten()
+            .map(|(name, dep)| (name.as_str(), dep))
+            .collect()
+    }
+
+    /// Returns every git dependency in the manifest, from the dependency
+    /// tables (including platform-specific ones and
+    /// `[workspace.dependencies]`) and `[patch]`.
+    pub fn git_dependencies(&self) -> Vec<GitDepInfo> {
+        self.all_dependency_tables()
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, dep)| match dep {
+                TomlDependency::Detailed(d) => d.git.as_ref().map(|url| GitDepInfo {
+                    name: name.clone(),
+                    url: url.clone(),
+                    reference: d.git_reference(),
+                }),
+                TomlDependency::Simple(_) | TomlDependency::Workspace(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns `true` if any dependency is taken from a registry other than
+    /// crates.io, either through `registry`/`registry-index` or a `[patch]`
+    /// of a named registry.
+    ///
+    /// This only looks at the manifest itself. `[patch]` keys that are URLs
+    /// are not counted, since a URL may name a git repository rather than a
+    /// registry and telling them apart needs the network.
+    pub fn uses_alt_registry(&self) -> bool {
+        let alt_dep =
+            self.all_dependency_tables()
+                .into_iter()
+                .flatten()
+                .any(|(_, dep)| match dep {
+                    TomlDependency::Detailed(d) => {
+                        d.registry.is_some() || d.registry_index.is_some()
+                    }
+                    TomlDependency::Simple(_) | TomlDependency::Workspace(_) => false,
+                });
+        let alt_patch = self
+            .patch
+            .iter()
+            .flat_map(|p| p.keys())
+            .any(|key| key != CRATES_IO_REGISTRY && key.into_url().is_err());
+        alt_dep || alt_patch
+    }
+
+    /// Every dependency table in the manifest: the normal, dev and build
+    /// tables, their platform-specific versions, `[workspace.dependencies]`
+    /// and `[patch]`.
+    fn all_dependency_tables(&self) -> Vec<&BTreeMap<String, TomlDependency>> {
+        let mut tables: Vec<&BTreeMap<String, TomlDependency>> =
+            [None, Some(DepKind::Development), Some(DepKind::Build)]
+                .into_iter()
+                .filter_map(|kind| self.dependency_table(kind))
+                .collect();
+        for platform in self.target.iter().flat_map(|t| t.values()) {
+            tables.extend(platform.dependencies.as_ref());
+            tables.extend(
+                platform
+                    .dev_dependencies
+                    .as_ref()
+                    .or(platform.dev_dependencies2.as_ref()),
+            );
+            tables.extend(
+                platform
+                    .build_dependencies
+                    .as_ref()
+                    .or(platform.build_dependencies2.as_ref()),
+            );
+        }
+        tables.extend(
+            self.workspace
+                .as_ref()
+                .and_then(|ws| ws.dependencies.as_ref()),
+        );
+        tables.extend(self.patch.iter().flat_map(|p| p.values()));
+        tables
+    }
+
+    fn dependency_table(&self, kind: Option<DepKind>) -> Option<&BTreeMap<String, TomlDependency>> {
+        match kind {
+            None | Some(DepKind::Normal) => self.dependencies.as_ref(),
+            Some(DepKind::Development) => self
+                .dev_dependencies
+                .as_ref()
+                .or_else(|| self.dev_dependencies2.as_ref()),
+            Some(DepKind::Build) => self
+                .build_dependencies
+                .as_ref()
+                .or_else(|| self.build_dependencies2.as_ref()),
+        }
+    }
+
     pub fn features(&self) -> Option<&BTreeMap<InternedString, Vec<InternedString>>> {
-        self.features.as_ref()
+        self.features.as_ref().map(|f| f.get_all())
+    }
+
+    /// Returns where the source file of each `[lib]`, `[[bin]]`,
+    /// `[[example]]`, `[[test]]` and `[[bench]]` target declared in the
+    /// manifest is, without looking at the filesystem: the explicit `path` if
+    /// set, otherwise the conventional location for its kind and name. A
+    /// `[[bin]]` named after the package is `src/main.rs`.
+    ///
+    /// Targets that are only inferred aren't listed, and neither are ones
+    /// whose location needs a `name` or `path` that isn't given.
+    pub fn declared_target_paths(&self, package_root: &Path) -> Vec<(TargetKind, PathBuf)> {
+        let package_name = match self.package.as_ref().or_else(|| self.project.as_ref()) {
+            Some(package) => package.name,
+            None => return Vec::new(),
+        };
+        let mut targets: Vec<(&TomlTarget, TargetKind)> = Vec::new();
+        targets.extend(self.lib.iter().map(|t| (t, TargetKind::Lib(Vec::new()))));
+        for (list, kind) in [
+            (&self.bin, TargetKind::Bin),
+            (&self.example, TargetKind::ExampleBin),
+            (&self.test, TargetKind::Test),
+            (&self.bench, TargetKind::Bench),
+        ] {
+            targets.extend(list.iter().flatten().map(|t| (t, kind.clone())));
+        }
+        targets
+            .into_iter()
+            .filter_map(|(target, kind)| {
+                let path = target.effective_path(package_root, &package_name, kind.clone())?;
+                Some((kind, path))
+            })
+            .collect()
+    }
+
+    /// Returns the `[package.metadata]` table, if any.
+    pub fn package_metadata(&self) -> Option<&toml::Value> {
+        self.package
+            .as_ref()
+            .or_else(|| self.project.as_ref())
+            .and_then(|p| p.custom_metadata())
     }
+
+    /// Returns the `[workspace.metadata]` table, if any.
+    pub fn workspace_metadata(&self) -> Option<&toml::Value> {
+        self.workspace.as_ref().and_then(|ws| ws.metadata.as_ref())
+    }
+
+    /// Returns the `[workspace]` table, if any.
+    pub fn workspace_section(&self) -> Option<&TomlWorkspace> {
+        self.workspace.as_ref()
+    }
+
+    /// Collapses the deprecated `snake_case` aliases (`dev_dependencies`,
+    /// `build_dependencies`, `default_features`, `crate_type` and
+    /// `proc_macro`) into their `kebab-case` fields, so tools only have to
+    /// look at one of them.
+    ///
+    /// A warning is pushed for every alias that was collapsed.
+    pub fn normalize_aliases(&mut self, warnings: &mut Vec<String>) {
+        let package_name = self
+            .package
+            .as_ref()
+            .or_else(|| self.project.as_ref())
+            .map(|p| p.name.to_string())
+            .unwrap_or_default();
+
+        collapse_alias(
+            &mut self.dev_dependencies,
+            &mut self.dev_dependencies2,
+            "dev-dependencies",
+            &package_name,
+            "package",
+            warnings,
+        );
+        collapse_alias(
+            &mut self.build_dependencies,
+            &mut self.build_dependencies2,
+            "build-dependencies",
+            &package_name,
+            "package",
+            warnings,
+        );
+        normalize_dependency_aliases(self.dependencies.as_mut(), warnings);
+        normalize_dependency_aliases(self.dev_dependencies.as_mut(), warnings);
+        normalize_dependency_aliases(self.build_dependencies.as_mut(), warnings);
+
+        for (name, platform) in self.target.iter_mut().flatten() {
+            collapse_alias(
+                &mut platform.dev_dependencies,
+                &mut platform.dev_dependencies2,
+                "dev-dependencies",
+                name,
+                "platform target",
+                warnings,
+            );
+            collapse_alias(
+                &mut platform.build_dependencies,
+                &mut platform.build_dependencies2,
+                "build-dependencies",
+                name,
+                "platform target",
+                warnings,
+            );
+            normalize_dependency_aliases(platform.dependencies.as_mut(), warnings);
+            normalize_dependency_aliases(platform.dev_dependencies.as_mut(), warnings);
+            normalize_dependency_aliases(platform.build_dependencies.as_mut(), warnings);
+        }
+
+        let targets = self
+            .lib
+            .iter_mut()
+            .map(|t| (t, "library target"))
+            .chain(self.bin.iter_mut().flatten().map(|t| (t, "binary target")))
+            .chain((self.example.iter_mut().flatten()).map(|t| (t, "example target")))
+            .chain(self.test.iter_mut().flatten().map(|t| (t, "test target")))
+            .chain(
+                self.bench
+                    .iter_mut()
+                    .flatten()
+                    .map(|t| (t, "benchmark target")),
+            );
+        for (target, kind) in targets {
+            let name = target.name.clone().unwrap_or_default();
+            collapse_alias(
+                &mut target.crate_type,
+                &mut target.crate_type2,
+                "crate-type",
+                &name,
+                kind,
+                warnings,
+            );
+            collapse_alias(
+                &mut target.proc_macro_raw,
+                &mut target.proc_macro_raw2,
+                "proc-macro",
+                &name,
+                kind,
+                warnings,
+            );
+        }
+    }
+
+    /// Returns a copy of this manifest in a canonical form, so that two
+    /// manifests that only differ in key order or in the spelling of aliased
+    /// fields serialize identically.
+    ///
+    /// The dependency, target, feature and profile tables are already kept in
+    /// key order. On top of that, the aliases are collapsed as in
+    /// [`TomlManifest::normalize_aliases`] and the free-form `metadata` tables,
+    /// which otherwise keep their input order, are sorted.
+    pub fn sorted(&self) -> TomlManifest {
+        let mut manifest = self.clone();
+        manifest.normalize_aliases(&mut Vec::new());
+        let package_metadata = manifest
+            .package
+            .iter_mut()
+            .chain(manifest.project.iter_mut())
+            .filter_map(|p| p.metadata.as_mut());
+        let workspace_metadata = manifest
+            .workspace
+            .iter_mut()
+            .filter_map(|ws| ws.metadata.as_mut());
+        for metadata in package_metadata.chain(workspace_metadata) {
+            sort_toml_tables(metadata);
+        }
+        manifest
+    }
+}
+
+/// Removes duplicate entries from `cargo-features`, keeping the first one and
+/// warning about the others.
+fn dedup_cargo_features(cargo_features: &[String], warnings: &mut Vec<String>) -> Vec<String> {
+    let mut deduped: Vec<String> = Vec::new();
+    for feature in cargo_features {
+        if deduped.contains(feature) {
+            warnings.push(format!(
+                "the cargo feature `{}` is listed more than once in `cargo-features`",
+                feature
+            ));
+        } else {
+            deduped.push(feature.clone());
+        }
+    }
+    deduped
 }
 
 fn inheritable_from_path(
@@ -2374,8 +4913,16 @@ fn inheritable_from_path(
 
 /// Returns the name of the README file for a `TomlProject`.
 pub fn readme_for_project(package_root: &Path, readme: Option<StringOrBool>) -> Option<String> {
+    readme_for_project_with(package_root, readme, &|path| path.is_file())
+}
+
+fn readme_for_project_with(
+    package_root: &Path,
+    readme: Option<StringOrBool>,
+    is_file: &dyn Fn(&Path) -> bool,
+) -> Option<String> {
     match &readme {
-        None => default_readme_from_package_root(package_root),
+        None => default_readme_from_package_root(package_root, is_file),
         Some(value) => match value {
             StringOrBool::Bool(false) => None,
             StringOrBool::Bool(true) => Some("README.md".to_string()),
@@ -2388,9 +4935,12 @@ const DEFAULT_README_FILES: [&str; 3] = ["README.md", "README.txt", "README"];
 
 /// Checks if a file with any of the default README file names exists in the package root.
 /// If so, returns a `String` representing that name.
-fn default_readme_from_package_root(package_root: &Path) -> Option<String> {
+fn default_readme_from_package_root(
+    package_root: &Path,
+    is_file: &dyn Fn(&Path) -> bool,
+) -> Option<String> {
     for &readme_filename in DEFAULT_README_FILES.iter() {
-        if package_root.join(readme_filename).is_file() {
+        if is_file(&package_root.join(readme_filename)) {
             return Some(readme_filename.to_string());
         }
     }
@@ -2398,6 +4948,73 @@ fn default_readme_from_package_root(package_root: &Path) -> Option<String> {
     None
 }
 
+/// Warns if a dependency's version requirement is a single exact `=` pin,
+/// which breaks the build once that version is yanked.
+fn warn_on_exact_pin(name: &str, dep: &TomlDependency, warnings: &mut Vec<String>) {
+    let req = match dep {
+        TomlDependency::Simple(version) => version,
+        TomlDependency::Detailed(d) => match &d.version {
+            Some(version) => version,
+            None => return,
+        },
+        TomlDependency::Workspace(_) => return,
+    };
+    let is_exact = match VersionReq::parse(req) {
+        Ok(parsed) => matches!(&parsed.comparators[..], [c] if c.op == semver::Op::Exact),
+        Err(_) => false,
+    };
+    if is_exact {
+        warnings.push(format!(
+            "dependency ({}) is pinned to the exact version `{}`, \
+             which fails to resolve if that version is yanked. \
+             Consider `\"{}\"` unless the pin is intentional.",
+            name,
+            req,
+            req.trim().trim_start_matches('=').trim()
+        ));
+    }
+}
+
+/// The file names Cargo looks for, in order, when `package.readme` is not set.
+pub fn default_readme_files() -> &'static [&'static str] {
+    &DEFAULT_README_FILES
+}
+
+/// Returns the name of the README Cargo would pick up by default in
+/// `package_root`, if any.
+pub fn find_default_readme(package_root: &Path) -> Option<String> {
+    default_readme_from_package_root(package_root, &|path| path.is_file())
+}
+
+/// Parses the value of `package.default-target` or `package.forced-target`.
+///
+/// Only the shape of the target triple is checked here, since the list of
+/// targets supported by `rustc` is not known while parsing the manifest.
+fn compile_kind_for_target(field: &str, triple: &str) -> CargoResult<CompileKind> {
+    let is_json = triple.ends_with(".json");
+    let components: Vec<_> = triple.split('-').collect();
+    // `arch-vendor-os` with an optional `-env`.
+    let is_well_formed = (3..=4).contains(&components.len())
+        && components.iter().all(|component| {
+            !component.is_empty()
+                && component
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        });
+    if !is_json && !is_well_formed {
+        bail!(
+            "`package.{}` value `{}` is not a valid target triple\n\
+             Built-in target triples are listed by `rustc --print target-list`, \
+             or the value may be a path to a custom target specification ending in `.json`.",
+            field,
+            triple
+        );
+    }
+    let target = CompileTarget::new(triple)
+        .with_context(|| format!("invalid `package.{}` value `{}`", field, triple))?;
+    Ok(CompileKind::Target(target))
+}
+
 /// Checks a list of build targets, and ensures the target names are unique within a vector.
 /// If not, the name of the offending build target is returned.
 fn unique_build_targets(targets: &[Target], package_root: &Path) -> Result<(), String> {
@@ -2413,7 +5030,55 @@ fn unique_build_targets(targets: &[Target], package_root: &Path) -> Result<(), S
     Ok(())
 }
 
-impl<P: ResolveToPath + Clone> TomlDependency<P> {
+/// Checks that a key in a dependency table is a valid name.
+///
+/// `package` is the value of the `package` key of the dependency, if the
+/// dependency was renamed.
+fn validate_dependency_name(name_in_toml: &str, package: Option<&str>) -> CargoResult<()> {
+    if validate_package_name(name_in_toml, "dependency name", "").is_ok() {
+        return Ok(());
+    }
+    let suggestion = suggested_dependency_name(name_in_toml);
+    let help = match package {
+        Some(package) => format!(
+            "\nThe dependency key only needs to be a valid name since the package is \
+             already set with `package = \"{}\"`, for example:\n\
+             {} = {{ package = \"{}\", ... }}",
+            package, suggestion, package
+        ),
+        None => format!(
+            "\nIf `{}` is the name of the package to depend on, use a valid dependency key \
+             and set the package name with `package = \"{}\"`, for example:\n\
+             {} = {{ package = \"{}\", ... }}",
+            name_in_toml, name_in_toml, suggestion, name_in_toml
+        ),
+    };
+    validate_package_name(name_in_toml, "dependency name", &help)
+}
+
+/// Builds a valid dependency key from an invalid one by replacing every
+/// disallowed character with `_`.
+fn suggested_dependency_name(name: &str) -> String {
+    let mut suggestion = String::with_capacity(name.len());
+    for (i, ch) in name.chars().enumerate() {
+        let valid = if i == 0 {
+            unicode_xid::UnicodeXID::is_xid_start(ch) || ch == '_'
+        } else {
+            unicode_xid::UnicodeXID::is_xid_continue(ch) || ch == '-'
+        };
+        if valid {
+            suggestion.push(ch);
+        } else if i == 0 && ch.is_ascii_digit() {
+            suggestion.push('_');
+            suggestion.push(ch);
+        } else {
+            suggestion.push('_');
+        }
+    }
+    suggestion
+}
+
+impl<P: ResolveToPath + Clone> TomlDependency<P> {
     pub(crate) fn to_dependency_split(
         &self,
         name: &str,
@@ -2474,6 +5139,19 @@ impl<P: ResolveToPath + Clone> TomlDependency<P> {
             TomlDependency::Workspace(w) => w.optional.unwrap_or(false),
         }
     }
+
+    fn package(&self) -> Option<&str> {
+        match self {
+            TomlDependency::Detailed(d) => d.package.as_deref(),
+            TomlDependency::Simple(..) | TomlDependency::Workspace(_) => None,
+        }
+    }
+
+    /// Returns the name of the package this dependency points to, which is
+    /// the `package` override if set and `toml_key` otherwise.
+    pub fn package_name<'a>(&'a self, toml_key: &'a str) -> &'a str {
+        self.package().unwrap_or(toml_key)
+    }
 }
 
 impl TomlDependency {
@@ -2490,6 +5168,7 @@ impl TomlDependency {
             TomlDependency::Workspace(TomlWorkspaceDependency {
                 workspace: true,
                 features,
+                inherit_features,
                 optional,
             }) => {
                 cargo_features.require(Feature::workspace_inheritance())?;
@@ -2513,9 +5192,13 @@ impl TomlDependency {
                         },
                         TomlDependency::Detailed(d) => {
                             let mut dep = d.clone();
-                            dep.add_features(features);
+                            if inherit_features == Some(false) {
+                                dep.features = features;
+                            } else {
+                                dep.add_features(features);
+                            }
                             dep.update_optional(optional);
-                            dep.resolve_path(label,inheritable.ws_root(), cx.root)?;
+                            dep.resolve_path(label, inheritable.dependency_root(label), cx.root)?;
                             Ok(TomlDependency::Detailed(dep))
                         },
                         TomlDependency::Workspace(_) => {
@@ -2568,6 +5251,14 @@ impl<P: ResolveToPath + Clone> DetailedTomlDependency<P> {
             }
         }
 
+        if self.package.as_deref() == Some(name_in_toml) {
+            cx.warnings.push(format!(
+                "dependency `{0}` sets `package = \"{0}\"`, which is the same as \
+                 its name; the `package` key can be removed",
+                name_in_toml
+            ));
+        }
+
         if self.git.is_none() {
             let git_only_keys = [
                 (&self.branch, "branch"),
@@ -2653,13 +5344,7 @@ impl<P: ResolveToPath + Clone> DetailedTomlDependency<P> {
                     );
                 }
 
-                let reference = self
-                    .branch
-                    .clone()
-                    .map(GitReference::Branch)
-                    .or_else(|| self.tag.clone().map(GitReference::Tag))
-                    .or_else(|| self.rev.clone().map(GitReference::Rev))
-                    .unwrap_or(GitReference::DefaultBranch);
+                let reference = self.git_reference();
                 let loc = git.into_url()?;
 
                 if let Some(fragment) = loc.fragment() {
@@ -2672,9 +5357,51 @@ impl<P: ResolveToPath + Clone> DetailedTomlDependency<P> {
                     cx.warnings.push(msg)
                 }
 
+                if loc.password().is_some() {
+                    cx.warnings.push(format!(
+                        "git URL for dependency ({}) contains a password, which is stored \
+                         in plain text in the manifest. \
+                         Consider using a git credential helper instead, see \
+                         https://doc.rust-lang.org/cargo/appendix/git-authentication.html",
+                        name_in_toml
+                    ));
+                }
+
+                // `refs/...` is fetched as-is, so only plain revs are checked.
+                if let Some(rev) = self.rev.as_deref().filter(|r| !r.starts_with("refs/")) {
+                    if !rev.chars().all(|c| c.is_ascii_hexdigit()) {
+                        cx.warnings.push(format!(
+                            "`rev` of dependency ({}) is `{}`, which does not look like \
+                             a commit hash. If it names a branch or tag, use \
+                             `branch = \"{}\"` or `tag = \"{}\"` instead.",
+                            name_in_toml, rev, rev, rev
+                        ));
+                    } else if rev.len() < 7 {
+                        cx.warnings.push(format!(
+                            "`rev` of dependency ({}) is `{}`, which is shorter than \
+                             7 characters and may match more than one commit",
+                            name_in_toml, rev
+                        ));
+                    }
+                }
+
                 SourceId::for_git(&loc, reference)?
             }
-            (None, Some(path), _, _) => {
+            (None, Some(path), registry, registry_index) => {
+                // With a `version`, the registry is where the dependency comes
+                // from once published. Without one, it is never used.
+                if self.version.is_none() && (registry.is_some() || registry_index.is_some()) {
+                    let key = if registry.is_some() {
+                        "registry"
+                    } else {
+                        "registry-index"
+                    };
+                    cx.warnings.push(format!(
+                        "dependency ({}) specifies both `path` and `{}` without a `version`, \
+                         `{}` is ignored",
+                        name_in_toml, key, key
+                    ));
+                }
                 let path = path.resolve(cx.config);
                 cx.nested_paths.push(path.clone());
                 // If the source ID for the package we're parsing is a path
@@ -2708,8 +5435,19 @@ impl<P: ResolveToPath + Clone> DetailedTomlDependency<P> {
 
         let version = self.version.as_deref();
         let mut dep = Dependency::parse(pkg_name, version, new_source_id)?;
-        if self.default_features.is_some() && self.default_features2.is_some() {
-            warn_on_deprecated("default-features", name_in_toml, "dependency", cx.warnings);
+        match (self.default_features, self.default_features2) {
+            (Some(hyphen), Some(underscore)) if hyphen != underscore => bail!(
+                "dependency ({}) specifies both `default-features = {}` and \
+                 `default_features = {}`, which contradict each other. \
+                 Remove `default_features`, it is a deprecated spelling of `default-features`.",
+                name_in_toml,
+                hyphen,
+                underscore
+            ),
+            (Some(_), Some(_)) => {
+                warn_on_deprecated("default-features", name_in_toml, "dependency", cx.warnings)
+            }
+            _ => {}
         }
         dep.set_features(self.features.iter().flatten())
             .set_default_features(
@@ -2753,6 +5491,33 @@ impl<P: ResolveToPath + Clone> DetailedTomlDependency<P> {
         ) {
             if cx.config.cli_unstable().bindeps {
                 let artifact = Artifact::parse(artifact, is_lib, target)?;
+                for kind in artifact.kinds() {
+                    if let ArtifactKind::SelectedBinary(bin_name) = kind {
+                        if let Err(e) = validate_package_name(bin_name, "binary name", "") {
+                            cx.warnings.push(format!(
+                                "artifact dependency `{}` selects `bin:{}`, \
+                                 which can never match a binary target: {}",
+                                name_in_toml, bin_name, e
+                            ));
+                        }
+                    }
+                }
+                if is_lib
+                    && artifact
+                        .kinds()
+                        .iter()
+                        .all(|k| matches!(k, ArtifactKind::Cdylib | ArtifactKind::Staticlib))
+                {
+                    let kinds: Vec<_> = artifact.kinds().iter().map(|k| k.to_string()).collect();
+                    cx.warnings.push(format!(
+                        "artifact dependency `{}` sets `lib = true` alongside only library \
+                         artifacts (`{}`), which also links the library as a Rust crate and \
+                         only works if its `[lib]` builds an `rlib` too. \
+                         Remove `lib = true` if only the artifact is needed.",
+                        name_in_toml,
+                        kinds.join("`, `")
+                    ));
+                }
                 if dep.kind() != DepKind::Build
                     && artifact.target() == Some(ArtifactTarget::BuildDependencyAssumeTarget)
                 {
@@ -2810,6 +5575,23 @@ impl DetailedTomlDependency {
         package_root: &Path,
     ) -> CargoResult<()> {
         if let Some(rel_path) = &self.path {
+            // The path must stay inside the workspace root, whether it is
+            // relative and climbs out with `..`, is absolute, or gets out
+            // through a symlink. Paths that don't exist yet can only be
+            // compared lexically.
+            let dep_path = paths::normalize_path(&root_path.join(rel_path));
+            let inside = match (dep_path.canonicalize(), root_path.canonicalize()) {
+                (Ok(dep_path), Ok(root_path)) => dep_path.starts_with(root_path),
+                _ => dep_path.starts_with(paths::normalize_path(root_path)),
+            };
+            if !inside {
+                bail!(
+                    "`{}` has a path `{}` that escapes the workspace root `{}`",
+                    name,
+                    rel_path,
+                    root_path.display()
+                )
+            }
             self.path = Some(resolve_relative_path(
                 name,
                 root_path,
@@ -2821,7 +5603,7 @@ impl DetailedTomlDependency {
     }
 }
 
-#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+#[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq)]
 struct TomlTarget {
     name: Option<String>,
 
@@ -2850,7 +5632,7 @@ struct TomlTarget {
     edition: Option<String>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 struct PathValue(PathBuf);
 
 impl<'de> de::Deserialize<'de> for PathValue {
@@ -2872,7 +5654,7 @@ impl ser::Serialize for PathValue {
 }
 
 /// Corresponds to a `target` entry, but `TomlTarget` is already used.
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 struct TomlPlatform {
     dependencies: Option<BTreeMap<String, TomlDependency>>,
     #[serde(rename = "build-dependencies")]
@@ -2890,6 +5672,27 @@ impl TomlTarget {
         TomlTarget::default()
     }
 
+    /// Where the source file of this target is, given the kind of target it
+    /// is: the explicit `path` if set, otherwise the conventional location.
+    ///
+    /// The filesystem is not consulted, so the alternate `<dir>/<name>/main.rs`
+    /// layouts aren't detected. A binary named after the package, or without
+    /// a `name`, is the package's default binary in `src/main.rs`. Returns
+    /// `None` for a test, bench, or example target without a `name` or `path`.
+    fn effective_path(
+        &self,
+        package_root: &Path,
+        package_name: &str,
+        kind: TargetKind,
+    ) -> Option<PathBuf> {
+        match &self.path {
+            Some(path) => Some(package_root.join(&path.0)),
+            None => {
+                targets::conventional_path(package_root, package_name, &kind, self.name.as_deref())
+            }
+        }
+    }
+
     fn name(&self) -> String {
         match self.name {
             Some(ref name) => name.clone(),
@@ -2942,3 +5745,1180 @@ impl fmt::Debug for PathValue {
         self.0.fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `Features` for a manifest declaring `cargo_features`, with nightly
+    /// features allowed.
+    fn nightly_features(cargo_features: &[&str]) -> Features {
+        let mut config = Config::default().unwrap();
+        config.nightly_features_allowed = true;
+        let cargo_features: Vec<_> = cargo_features.iter().map(|f| f.to_string()).collect();
+        Features::new(&cargo_features, &config, &mut Vec::new(), true).unwrap()
+    }
+
+    #[test]
+    fn unresolved_workspace_fields() {
+        let project: TomlProject = toml::from_str(
+            r#"
+                name = "foo"
+                version.workspace = true
+                description = "a package"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(project.unresolved_workspace_fields(), vec!["version"]);
+    }
+
+    #[test]
+    fn builtin_profiles() {
+        let release = TomlProfile::builtin("release").unwrap();
+        assert_eq!(release.opt_level, Some(TomlOptLevel("3".to_string())));
+        assert_eq!(release.debug, Some(TomlDebugInfo::Bool(false)));
+
+        let dev = TomlProfile::builtin("dev").unwrap();
+        assert_eq!(dev.opt_level, Some(TomlOptLevel("0".to_string())));
+        assert_eq!(dev.debug, Some(TomlDebugInfo::Bool(true)));
+
+        assert_eq!(TomlProfile::builtin("test"), Some(dev));
+        assert_eq!(TomlProfile::builtin("bench"), Some(release));
+        assert_eq!(TomlProfile::builtin("custom"), None);
+    }
+
+    #[test]
+    fn merge_reporting_profiles() {
+        let mut profile: TomlProfile = toml::from_str(
+            r#"
+                opt-level = 1
+                debug = true
+                lto = "thin"
+
+                [build-override]
+                opt-level = 0
+            "#,
+        )
+        .unwrap();
+        let overlay: TomlProfile = toml::from_str(
+            r#"
+                opt-level = 2
+                debug = true
+                incremental = false
+
+                [build-override]
+                opt-level = 3
+            "#,
+        )
+        .unwrap();
+
+        let changed = profile.merge_reporting(&overlay);
+        assert_eq!(changed, vec!["opt-level", "build-override.opt-level"]);
+        assert_eq!(profile.opt_level, Some(TomlOptLevel("2".to_string())));
+        assert_eq!(profile.lto, Some(StringOrBool::String("thin".to_string())));
+        assert_eq!(profile.incremental, Some(false));
+        assert!(profile.merge_reporting(&overlay).is_empty());
+    }
+
+    #[test]
+    fn manifest_limits() {
+        let manifest = r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            a = "1"
+            b = { version = "1", features = ["x"] }
+
+            [target.'cfg(unix)'.dependencies]
+            c = "1"
+        "#;
+        let document: toml_edit::Document = manifest.parse().unwrap();
+        let limits = ManifestLimits {
+            max_bytes: 1024,
+            max_depth: 4,
+            max_dependencies: 3,
+        };
+        limits.check_size(manifest).unwrap();
+        limits.check_document(&document).unwrap();
+
+        let limits = ManifestLimits {
+            max_dependencies: 2,
+            ..limits
+        };
+        let err = limits.check_document(&document).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "manifest declares 3 dependencies, which exceeds the limit of 2"
+        );
+
+        let limits = ManifestLimits {
+            max_depth: 3,
+            max_dependencies: 3,
+            ..limits
+        };
+        let err = limits.check_document(&document).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "manifest has tables or arrays nested 4 levels deep, which exceeds the limit of 3"
+        );
+
+        let limits = ManifestLimits {
+            max_bytes: 16,
+            ..limits
+        };
+        assert!(limits.check_size(manifest).is_err());
+    }
+
+    #[test]
+    fn inheritance_errors() {
+        let mut fields: InheritableFields = toml::from_str(
+            r#"
+                version = "1.0.0"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(fields.version().unwrap().to_string(), "1.0.0");
+        assert!(matches!(
+            fields.rust_version(),
+            Err(InheritanceError::FieldNotDefined("rust-version"))
+        ));
+        assert!(matches!(
+            fields.license_file(Path::new("")),
+            Err(InheritanceError::FieldNotDefined("license-file"))
+        ));
+        assert!(matches!(
+            fields.get_dependency("dep"),
+            Err(InheritanceError::DependenciesNotDefined)
+        ));
+
+        fields.update_deps(Some(BTreeMap::new()));
+        let err = fields.get_dependency("dep").unwrap_err();
+        assert!(matches!(&err, InheritanceError::DependencyNotFound(name) if name == "dep"));
+        assert_eq!(
+            err.to_string(),
+            "`dependency.dep` was not found in `workspace.dependencies`"
+        );
+    }
+
+    #[test]
+    fn decode_manifest_contents() {
+        let clean = "[package]\nname = \"foo\"\n";
+        assert_eq!(decode_manifest(clean.as_bytes()).unwrap(), clean);
+        assert!(matches!(
+            decode_manifest(clean.as_bytes()).unwrap(),
+            Cow::Borrowed(_)
+        ));
+
+        let bom = format!("\u{feff}{}", clean);
+        assert_eq!(decode_manifest(bom.as_bytes()).unwrap(), clean);
+
+        let crlf = clean.replace("\n", "\r\n");
+        assert_eq!(decode_manifest(crlf.as_bytes()).unwrap(), clean);
+
+        let err = decode_manifest(b"[package]\nname = \"\xff\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "manifest is not valid UTF-8: invalid byte sequence at byte offset 18"
+        );
+    }
+
+    #[test]
+    fn parse_without_config() {
+        let manifest = r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+        "#;
+
+        let value = parse_str(manifest).unwrap();
+        assert_eq!(value["package"]["name"].as_str(), Some("foo"));
+        let document = parse_document_str(manifest).unwrap();
+        assert_eq!(document["package"]["version"].as_str(), Some("0.1.0"));
+
+        let err = parse_str("[package").unwrap_err();
+        assert_eq!(err.to_string(), "could not parse input as TOML");
+        assert!(parse_document_str("[package").is_err());
+    }
+
+    #[test]
+    fn normalize_aliases() {
+        let mut manifest: TomlManifest = toml::from_str(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [lib]
+                crate_type = ["rlib"]
+                proc_macro = false
+
+                [dependencies]
+                bar = { version = "1.0", default_features = false }
+
+                [dev_dependencies]
+                baz = "1.0"
+
+                [build-dependencies]
+                qux = "1.0"
+
+                [build_dependencies]
+                ignored = "1.0"
+
+                [target.'cfg(unix)'.build_dependencies]
+                quux = "1.0"
+            "#,
+        )
+        .unwrap();
+
+        let mut warnings = Vec::new();
+        manifest.normalize_aliases(&mut warnings);
+
+        assert!(manifest.dev_dependencies.unwrap().contains_key("baz"));
+        assert!(manifest.dev_dependencies2.is_none());
+        let build_deps = manifest.build_dependencies.unwrap();
+        assert!(build_deps.contains_key("qux"));
+        assert!(!build_deps.contains_key("ignored"));
+        assert!(manifest.build_dependencies2.is_none());
+        match &manifest.dependencies.unwrap()["bar"] {
+            TomlDependency::Detailed(details) => {
+                assert_eq!(details.default_features, Some(false));
+                assert_eq!(details.default_features2, None);
+            }
+            dep => panic!("unexpected dependency {:?}", dep),
+        }
+        let platform = &manifest.target.unwrap()["cfg(unix)"];
+        assert!(platform
+            .build_dependencies
+            .as_ref()
+            .unwrap()
+            .contains_key("quux"));
+        assert!(platform.build_dependencies2.is_none());
+        let lib = manifest.lib.unwrap();
+        assert_eq!(lib.crate_type, Some(vec!["rlib".to_string()]));
+        assert_eq!(lib.crate_type2, None);
+        assert_eq!(lib.proc_macro_raw, Some(false));
+        assert_eq!(lib.proc_macro_raw2, None);
+
+        assert_eq!(warnings.len(), 6);
+        assert!(warnings[0].starts_with(
+            "`dev_dependencies` in the `foo` package was normalized to `dev-dependencies`"
+        ));
+        assert!(warnings[1].starts_with("conflicting between `build-dependencies`"));
+    }
+
+    #[test]
+    fn target_effective_path() {
+        let root = Path::new("/pkg");
+        let target = |toml: &str| -> TomlTarget { toml::from_str(toml).unwrap() };
+
+        let explicit = target(
+            r#"
+                name = "foo"
+                path = "src/foo/entry.rs"
+            "#,
+        );
+        assert_eq!(
+            explicit.effective_path(root, "pkg", TargetKind::Bin),
+            Some(root.join("src/foo/entry.rs"))
+        );
+
+        let unnamed = TomlTarget::new();
+        assert_eq!(
+            unnamed.effective_path(root, "pkg", TargetKind::Lib(vec![])),
+            Some(root.join("src/lib.rs"))
+        );
+        assert_eq!(
+            unnamed.effective_path(root, "pkg", TargetKind::Bin),
+            Some(root.join("src/main.rs"))
+        );
+        assert_eq!(
+            unnamed.effective_path(root, "pkg", TargetKind::CustomBuild),
+            Some(root.join("build.rs"))
+        );
+        assert_eq!(unnamed.effective_path(root, "pkg", TargetKind::Test), None);
+
+        let default_bin = target(r#"name = "pkg""#);
+        assert_eq!(
+            default_bin.effective_path(root, "pkg", TargetKind::Bin),
+            Some(root.join("src/main.rs"))
+        );
+        assert_eq!(
+            default_bin.effective_path(root, "pkg", TargetKind::ExampleBin),
+            Some(root.join("examples/pkg.rs"))
+        );
+
+        let named = target(r#"name = "foo""#);
+        assert_eq!(
+            named.effective_path(root, "pkg", TargetKind::Bin),
+            Some(root.join("src/bin/foo.rs"))
+        );
+        assert_eq!(
+            named.effective_path(root, "pkg", TargetKind::ExampleBin),
+            Some(root.join("examples/foo.rs"))
+        );
+        assert_eq!(
+            named.effective_path(root, "pkg", TargetKind::Test),
+            Some(root.join("tests/foo.rs"))
+        );
+        assert_eq!(
+            named.effective_path(root, "pkg", TargetKind::Bench),
+            Some(root.join("benches/foo.rs"))
+        );
+
+        let manifest: TomlManifest = toml::from_str(
+            r#"
+                [package]
+                name = "pkg"
+                version = "0.1.0"
+
+                [[bin]]
+                name = "pkg"
+
+                [[bin]]
+                name = "tool"
+
+                [[example]]
+                name = "demo"
+                path = "examples/demo/entry.rs"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.declared_target_paths(root),
+            vec![
+                (TargetKind::Bin, root.join("src/main.rs")),
+                (TargetKind::Bin, root.join("src/bin/tool.rs")),
+                (TargetKind::ExampleBin, root.join("examples/demo/entry.rs")),
+            ]
+        );
+    }
+
+    #[test]
+    fn sorted_manifests_serialize_identically() {
+        let a: TomlManifest = toml::from_str(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [package.metadata.tool]
+                b = 1
+                a = { y = 2, x = 3 }
+
+                [dev_dependencies]
+                zed = "1"
+                alpha = { version = "1", default_features = false }
+
+                [target.'cfg(windows)'.dependencies]
+                winapi = "0.3"
+
+                [target.'cfg(unix)'.dependencies]
+                libc = "0.2"
+            "#,
+        )
+        .unwrap();
+        let b: TomlManifest = toml::from_str(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [package.metadata.tool]
+                a = { x = 3, y = 2 }
+                b = 1
+
+                [dev-dependencies]
+                alpha = { version = "1", default-features = false }
+                zed = "1"
+
+                [target.'cfg(unix)'.dependencies]
+                libc = "0.2"
+
+                [target.'cfg(windows)'.dependencies]
+                winapi = "0.3"
+            "#,
+        )
+        .unwrap();
+
+        assert_ne!(toml::to_string(&a).unwrap(), toml::to_string(&b).unwrap());
+        assert_eq!(
+            toml::to_string(&a.sorted()).unwrap(),
+            toml::to_string(&b.sorted()).unwrap()
+        );
+    }
+
+    #[test]
+    fn debug_info_forms() {
+        let debug = |value: &str| -> Result<TomlDebugInfo, _> {
+            toml::from_str::<TomlProfile>(&format!("debug = {}", value)).map(|p| p.debug.unwrap())
+        };
+
+        for (value, level) in [
+            ("false", None),
+            ("true", Some(2)),
+            ("0", Some(0)),
+            ("1", Some(1)),
+            ("2", Some(2)),
+            ("\"none\"", Some(0)),
+            ("\"line-directives-only\"", Some(1)),
+            ("\"line-tables-only\"", Some(1)),
+            ("\"limited\"", Some(1)),
+            ("\"full\"", Some(2)),
+        ] {
+            let parsed = debug(value).unwrap();
+            assert_eq!(parsed.level(), level, "{}", value);
+            // The original spelling is kept when serializing.
+            assert_eq!(toml::Value::try_from(&parsed).unwrap().to_string(), value);
+        }
+        assert!(debug("\"partial\"").is_err());
+        assert!(debug("-1").is_err());
+    }
+
+    #[test]
+    fn dependency_source_kind() {
+        let kind = |toml: &str| {
+            let dep: TomlDependency = toml::from_str::<BTreeMap<String, TomlDependency>>(toml)
+                .unwrap()
+                .remove("dep")
+                .unwrap();
+            dep.source_kind()
+        };
+        assert_eq!(kind(r#"dep = "1.0""#), DependencySourceKind::Registry);
+        assert_eq!(
+            kind(r#"dep = { version = "1.0" }"#),
+            DependencySourceKind::Registry
+        );
+        assert_eq!(
+            kind(r#"dep = { version = "1.0", registry = "alt" }"#),
+            DependencySourceKind::AltRegistry
+        );
+        assert_eq!(
+            kind(r#"dep = { path = "../dep", registry = "alt" }"#),
+            DependencySourceKind::Path
+        );
+        assert_eq!(
+            kind(r#"dep = { git = "https://example.com/dep", path = "dep" }"#),
+            DependencySourceKind::Git
+        );
+        assert_eq!(
+            kind(r#"dep = { workspace = true }"#),
+            DependencySourceKind::Workspace
+        );
+    }
+
+    #[test]
+    fn profile_to_minimal_toml() {
+        let release_default = TomlProfile {
+            opt_level: Some(TomlOptLevel("3".to_string())),
+            debug: Some(TomlDebugInfo::Bool(false)),
+            debug_assertions: Some(false),
+            overflow_checks: Some(false),
+            incremental: Some(false),
+            codegen_units: Some(16),
+            ..Default::default()
+        };
+        let profile: TomlProfile = toml::from_str(
+            r#"
+                opt-level = 3
+                debug = false
+                debug-assertions = false
+                overflow-checks = false
+                incremental = false
+                codegen-units = 1
+                lto = true
+
+                [build-override]
+                opt-level = 0
+            "#,
+        )
+        .unwrap();
+
+        let minimal = profile.to_minimal_toml(&release_default).unwrap();
+        let minimal: TomlProfile = toml::from_str(&minimal).unwrap();
+        assert_eq!(
+            minimal,
+            TomlProfile {
+                codegen_units: Some(1),
+                lto: Some(StringOrBool::Bool(true)),
+                build_override: Some(Box::new(TomlProfile {
+                    opt_level: Some(TomlOptLevel("0".to_string())),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            release_default.to_minimal_toml(&release_default).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn resolve_single_field() {
+        let features = nightly_features(&["workspace-inheritance"]);
+        let inheritable: InheritableFields = toml::from_str(
+            r#"
+                version = "1.2.3"
+                description = "from the workspace"
+                keywords = ["cli"]
+            "#,
+        )
+        .unwrap();
+        fn inherited<T>() -> MaybeWorkspace<T> {
+            MaybeWorkspace::Workspace(TomlWorkspaceField {
+                workspace: true,
+                ..Default::default()
+            })
+        }
+
+        assert_eq!(
+            resolve_field(
+                MaybeWorkspace::Defined("defined".to_string()),
+                &inheritable,
+                &features,
+                "description"
+            )
+            .unwrap(),
+            "defined"
+        );
+        assert_eq!(
+            resolve_field(
+                inherited::<String>(),
+                &inheritable,
+                &features,
+                "description"
+            )
+            .unwrap(),
+            "from the workspace"
+        );
+        assert_eq!(
+            resolve_field(
+                inherited::<semver::Version>(),
+                &inheritable,
+                &features,
+                "version"
+            )
+            .unwrap(),
+            semver::Version::new(1, 2, 3)
+        );
+
+        let err =
+            resolve_field(inherited::<String>(), &inheritable, &features, "homepage").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "error inheriting `homepage` from workspace root manifest's \
+             `workspace.package.homepage`: `workspace.package.homepage` was not defined"
+        );
+    }
+
+    #[test]
+    fn resolve_all_inheritance_collects_errors() {
+        let features = nightly_features(&["workspace-inheritance"]);
+        let inheritable: InheritableFields = toml::from_str(
+            r#"
+                version = "1.2.3"
+                edition = "2021"
+            "#,
+        )
+        .unwrap();
+        let project: TomlProject = toml::from_str(
+            r#"
+                name = "foo"
+                version.workspace = true
+                edition.workspace = true
+                description.workspace = true
+                license.workspace = true
+                homepage = "https://example.com"
+            "#,
+        )
+        .unwrap();
+
+        let (resolved, errors) = project.resolve_all_inheritance(&inheritable, &features);
+        let errors: Vec<_> = errors.iter().map(|e| format!("{:#}", e)).collect();
+        assert_eq!(
+            errors,
+            [
+                "error inheriting `description` from workspace root manifest's \
+                 `workspace.package.description`: `workspace.package.description` was not defined",
+                "error inheriting `license` from workspace root manifest's \
+                 `workspace.package.license`: `workspace.package.license` was not defined",
+            ]
+        );
+        assert_eq!(
+            resolved.version.as_ref().and_then(|v| v.as_defined()),
+            Some(&semver::Version::new(1, 2, 3))
+        );
+        assert_eq!(
+            resolved
+                .edition
+                .as_ref()
+                .and_then(|e| e.as_defined())
+                .map(String::as_str),
+            Some("2021")
+        );
+        assert!(matches!(
+            resolved.description,
+            Some(MaybeWorkspace::Workspace(_))
+        ));
+        assert!(matches!(
+            resolved.license,
+            Some(MaybeWorkspace::Workspace(_))
+        ));
+    }
+
+    #[test]
+    fn manifest_round_trips_structurally() {
+        let manifest: TomlManifest = toml::from_str(
+            r#"
+                cargo-features = ["workspace-inheritance"]
+
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition.workspace = true
+                keywords = ["cli"]
+
+                [package.metadata.docs]
+                all-features = true
+                ratio = 0.5
+
+                [lib]
+                path = "src/foo.rs"
+
+                [[bin]]
+                name = "foo-cli"
+
+                [dependencies]
+                simple = "1.0"
+                detailed = { version = "1.0", features = ["a"], optional = true }
+                inherited = { workspace = true, features = ["b"] }
+
+                [target.'cfg(unix)'.dev-dependencies]
+                unix = { git = "https://example.com/unix", branch = "main" }
+
+                [features]
+                default = ["detailed"]
+
+                [profile.release]
+                opt-level = "z"
+                debug = "limited"
+
+                [workspace]
+                members = ["bar"]
+
+                [workspace.package]
+                edition = "2021"
+
+                [workspace.dependencies]
+                inherited = "2.0"
+            "#,
+        )
+        .unwrap();
+
+        let serialized = toml::to_string(&manifest).unwrap();
+        let reparsed: TomlManifest = toml::from_str(&serialized).unwrap();
+        assert_eq!(manifest, reparsed);
+
+        let mut changed = reparsed.clone();
+        changed.cargo_features = None;
+        assert_ne!(manifest, changed);
+    }
+
+    #[test]
+    fn uses_alt_registry() {
+        fn parse(s: &str) -> TomlManifest {
+            toml::from_str(s).unwrap()
+        }
+        let crates_io = r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dependencies]
+            bar = "1.0"
+            baz = { version = "1.0", features = ["std"] }
+            local = { path = "local" }
+
+            [patch.crates-io]
+            bar = { git = "https://example.com/bar" }
+
+            [patch."https://example.com/baz"]
+            baz = { path = "baz" }
+        "#;
+        assert!(!parse(crates_io).uses_alt_registry());
+
+        let alt_dep = r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [target.'cfg(unix)'.dev-dependencies]
+            bar = { version = "1.0", registry = "alternative" }
+        "#;
+        assert!(parse(alt_dep).uses_alt_registry());
+
+        let alt_index = r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [build-dependencies]
+            bar = { version = "1.0", registry-index = "https://example.com/index" }
+        "#;
+        assert!(parse(alt_index).uses_alt_registry());
+
+        let alt_patch = r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [patch.alternative]
+            bar = { path = "bar" }
+        "#;
+        assert!(parse(alt_patch).uses_alt_registry());
+    }
+
+    #[test]
+    fn parse_dependencies_table() {
+        let deps = parse_dependencies(
+            r#"
+                [dependencies]
+                simple = "1.0"
+                detailed = { version = "0.2", features = ["std"], optional = true }
+
+                [dependencies.git]
+                git = "https://example.com/git"
+                branch = "dev"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            deps.keys().collect::<Vec<_>>(),
+            ["detailed", "git", "simple"]
+        );
+        assert_eq!(deps["simple"], TomlDependency::Simple("1.0".to_string()));
+        match &deps["detailed"] {
+            TomlDependency::Detailed(d) => {
+                assert_eq!(d.version.as_deref(), Some("0.2"));
+                assert_eq!(d.features, Some(vec!["std".to_string()]));
+                assert_eq!(d.optional, Some(true));
+            }
+            dep => panic!("expected a detailed dependency, got {:?}", dep),
+        }
+        match &deps["git"] {
+            TomlDependency::Detailed(d) => {
+                assert_eq!(d.git.as_deref(), Some("https://example.com/git"));
+                assert_eq!(d.branch.as_deref(), Some("dev"));
+            }
+            dep => panic!("expected a detailed dependency, got {:?}", dep),
+        }
+
+        let err = parse_dependencies("[dev-dependencies]\nfoo = \"1.0\"\n").unwrap_err();
+        assert_eq!(err.to_string(), "no `[dependencies]` table found");
+
+        let err = parse_dependencies("[dependencies]\nfoo = 1\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse the `[dependencies]` table"
+        );
+    }
+
+    #[test]
+    fn inheritable_fields_overlay() {
+        let mut base: InheritableFields = toml::from_str(
+            r#"
+                version = "1.0.0"
+                description = "base"
+                license = "MIT"
+                readme = "docs/README.md"
+            "#,
+        )
+        .unwrap();
+        base.update_ws_path(PathBuf::from("/root"));
+        base.update_deps(Some(
+            parse_dependencies(
+                r#"
+                [dependencies]
+                shared = "1.0"
+                base-only = { path = "crates/base-only" }
+            "#,
+            )
+            .unwrap(),
+        ));
+
+        let mut top: InheritableFields = toml::from_str(
+            r#"
+                version = "2.0.0"
+                homepage = "https://example.com"
+            "#,
+        )
+        .unwrap();
+        top.update_ws_path(PathBuf::from("/root/sub"));
+        top.update_deps(Some(
+            parse_dependencies(
+                r#"
+                [dependencies]
+                shared = "2.0"
+                top-only = "0.2"
+            "#,
+            )
+            .unwrap(),
+        ));
+
+        let merged = base.overlay(&top);
+        assert_eq!(merged.version().unwrap().to_string(), "2.0.0");
+        assert_eq!(merged.description().unwrap(), "base");
+        assert_eq!(merged.license().unwrap(), "MIT");
+        assert_eq!(merged.homepage().unwrap(), "https://example.com");
+        assert!(merged.documentation().is_err());
+        assert_eq!(merged.ws_root(), Path::new("/root/sub"));
+
+        // Paths from the base layer stay relative to the base's root.
+        assert_eq!(
+            merged.readme(Path::new("/root/sub/member")).unwrap(),
+            StringOrBool::String("../../docs/README.md".to_string())
+        );
+        assert_eq!(merged.dependency_root("base-only"), Path::new("/root"));
+        assert_eq!(merged.dependency_root("shared"), Path::new("/root/sub"));
+
+        let deps = merged.dependencies().unwrap();
+        assert_eq!(
+            deps.keys().collect::<Vec<_>>(),
+            ["base-only", "shared", "top-only"]
+        );
+        assert_eq!(deps["shared"], TomlDependency::Simple("2.0".to_string()));
+
+        // A side without dependencies keeps the other side's.
+        top.update_deps(None);
+        assert_eq!(base.overlay(&top).dependencies().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn key_line_finds_tables_and_keys() {
+        let document: toml_edit::Document = r#"[package]
+name = "foo"
+keywords = [
+    "[dev_dependencies]",
+]
+
+[dev-dependencies]
+a = "1"
+
+# comment
+[dev_dependencies]
+a = "1"
+
+[target.'cfg(unix)']
+build_dependencies = { b = "1" }
+
+[target.'cfg(windows)'.dev_dependencies.c]
+version = "1"
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(key_line(&document, &["dev_dependencies"]), Some(11));
+        assert_eq!(key_line(&document, &["dev-dependencies", "a"]), Some(8));
+        assert_eq!(
+            key_line(&document, &["target", "cfg(unix)", "build_dependencies"]),
+            Some(15)
+        );
+        assert_eq!(
+            key_line(&document, &["target", "cfg(windows)", "dev_dependencies"]),
+            Some(17)
+        );
+        assert_eq!(key_line(&document, &["build_dependencies"]), None);
+    }
+
+    #[test]
+    fn project_compile_kinds() {
+        fn project(s: &str) -> TomlProject {
+            let manifest: TomlManifest = toml::from_str(s).unwrap();
+            *manifest.package.unwrap()
+        }
+
+        let unset = project("[package]\nname = \"foo\"\nversion = \"0.1.0\"\n");
+        assert_eq!(unset.default_compile_kind().unwrap(), None);
+        assert_eq!(unset.forced_compile_kind().unwrap(), None);
+
+        let set = project(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                default-target = "x86_64-unknown-linux-gnu"
+                forced-target = "wasm32-unknown-unknown"
+            "#,
+        );
+        assert_eq!(
+            set.default_compile_kind().unwrap(),
+            Some(CompileKind::Target(
+                CompileTarget::new("x86_64-unknown-linux-gnu").unwrap()
+            ))
+        );
+        assert_eq!(
+            set.forced_compile_kind().unwrap(),
+            Some(CompileKind::Target(
+                CompileTarget::new("wasm32-unknown-unknown").unwrap()
+            ))
+        );
+
+        let invalid = project(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                default-target = "x64-linux"
+            "#,
+        );
+        let err = invalid.default_compile_kind().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`package.default-target` value `x64-linux` is not a valid target triple\n\
+             Built-in target triples are listed by `rustc --print target-list`, \
+             or the value may be a path to a custom target specification ending in `.json`."
+        );
+    }
+
+    #[test]
+    fn resolve_package_profile_precedence() {
+        let profile: TomlProfile = toml::from_str(
+            r#"
+                opt-level = 1
+
+                [package."*"]
+                opt-level = 2
+                debug-assertions = false
+
+                [package."foo:1.0.0"]
+                opt-level = 3
+            "#,
+        )
+        .unwrap();
+        let source = SourceId::for_path(Path::new("/")).unwrap();
+        let foo = PackageId::new("foo", "1.0.0", source).unwrap();
+        let bar = PackageId::new("bar", "1.0.0", source).unwrap();
+        let foo2 = PackageId::new("foo", "2.0.0", source).unwrap();
+
+        // The specific spec wins over the wildcard, which still fills in the
+        // settings the spec leaves alone.
+        let resolved = profile.resolve_package_profile(foo, false).unwrap();
+        assert_eq!(resolved.opt_level, Some(TomlOptLevel("3".to_string())));
+        assert_eq!(resolved.debug_assertions, Some(false));
+
+        // Packages without their own spec only get the wildcard.
+        for pkg in [bar, foo2] {
+            let resolved = profile.resolve_package_profile(pkg, false).unwrap();
+            assert_eq!(resolved.opt_level, Some(TomlOptLevel("2".to_string())));
+            assert_eq!(resolved.debug_assertions, Some(false));
+        }
+
+        // The wildcard doesn't apply to workspace members.
+        let resolved = profile.resolve_package_profile(foo, true).unwrap();
+        assert_eq!(resolved.opt_level, Some(TomlOptLevel("3".to_string())));
+        assert_eq!(resolved.debug_assertions, None);
+        assert_eq!(
+            profile.resolve_package_profile(bar, true).unwrap(),
+            TomlProfile::default()
+        );
+
+        // Without any overrides nothing is set, not even the profile's own
+        // settings.
+        let plain: TomlProfile = toml::from_str("opt-level = 1").unwrap();
+        assert_eq!(
+            plain.resolve_package_profile(foo, false).unwrap(),
+            TomlProfile::default()
+        );
+
+        // A package matching more than one spec is ambiguous.
+        let ambiguous: TomlProfile = toml::from_str(
+            r#"
+                [package.foo]
+                opt-level = 2
+
+                [package."foo:1.0.0"]
+                opt-level = 3
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            ambiguous
+                .resolve_package_profile(foo, false)
+                .unwrap_err()
+                .to_string(),
+            "multiple package overrides match package `foo v1.0.0 (/)`\n\
+             found package specs: foo, foo@1.0.0"
+        );
+    }
+
+    #[test]
+    fn dependency_declared_features() {
+        let deps = parse_dependencies(
+            r#"
+                [dependencies]
+                simple = "1.0"
+                detailed = { version = "1.0", features = ["a", "b"] }
+                detailed-none = { version = "1.0" }
+                inherited = { workspace = true, features = ["c"] }
+                inherited-none = { workspace = true }
+            "#,
+        )
+        .unwrap();
+        assert!(deps["simple"].declared_features().is_empty());
+        assert_eq!(deps["detailed"].declared_features(), ["a", "b"]);
+        assert!(deps["detailed-none"].declared_features().is_empty());
+        assert_eq!(deps["inherited"].declared_features(), ["c"]);
+        assert!(deps["inherited-none"].declared_features().is_empty());
+    }
+
+    #[test]
+    fn maybe_workspace_predicates() {
+        let defined: MaybeWorkspace<String> = MaybeWorkspace::Defined("1.0.0".to_string());
+        assert!(defined.is_defined());
+        assert!(!defined.is_workspace());
+        assert_eq!(defined.into_defined().as_deref(), Some("1.0.0"));
+
+        let workspace: MaybeWorkspace<String> = MaybeWorkspace::Workspace(TomlWorkspaceField {
+            workspace: true,
+            at_least: None,
+        });
+        assert!(workspace.is_workspace());
+        assert!(!workspace.is_defined());
+        assert_eq!(workspace.into_defined(), None);
+    }
+
+    #[test]
+    fn workspace_members_from_root_manifest() {
+        let contents = r#"
+            [workspace]
+            members = ["crates/*", "tools/cli"]
+            default-members = ["crates/core"]
+            exclude = ["crates/scratch"]
+
+            [workspace.package]
+            version = "1.0.0"
+        "#;
+        let (members, default_members, exclude) =
+            read_workspace_members(contents).unwrap().unwrap();
+        assert_eq!(members, ["crates/*", "tools/cli"]);
+        assert_eq!(default_members, ["crates/core"]);
+        assert_eq!(exclude, ["crates/scratch"]);
+
+        let manifest: TomlManifest = toml::from_str(contents).unwrap();
+        let workspace = manifest.workspace_section().unwrap();
+        assert_eq!(workspace.members(), ["crates/*", "tools/cli"]);
+        assert_eq!(workspace.default_members(), ["crates/core"]);
+        assert_eq!(workspace.exclude(), ["crates/scratch"]);
+
+        let (members, default_members, exclude) =
+            read_workspace_members("[workspace]").unwrap().unwrap();
+        assert!(members.is_empty() && default_members.is_empty() && exclude.is_empty());
+
+        let package = "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n";
+        assert!(read_workspace_members(package).unwrap().is_none());
+        let manifest: TomlManifest = toml::from_str(package).unwrap();
+        assert!(manifest.workspace_section().is_none());
+    }
+
+    #[test]
+    fn gate_unstable_edition() {
+        // No edition is unstable right now, so stand in a stable edition and
+        // an unstable feature for the table entry.
+        let gates: &[(Edition, fn() -> &'static Feature)] =
+            &[(Edition::Edition2021, Feature::test_dummy_unstable)];
+        let without = nightly_features(&[]);
+        let err = gate_edition_with(Edition::Edition2021, &without, gates).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("feature `test-dummy-unstable` is required"));
+        gate_edition_with(Edition::Edition2018, &without, gates).unwrap();
+        gate_edition(Edition::Edition2021, &without).unwrap();
+
+        let with = nightly_features(&["test-dummy-unstable"]);
+        gate_edition_with(Edition::Edition2021, &with, gates).unwrap();
+    }
+
+    #[test]
+    fn dependency_package_name() {
+        let manifest: TomlManifest = toml::from_str(
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                bar = "1.0"
+                baz = { version = "1.0", package = "real-baz" }
+                qux = { version = "1.0" }
+                quux = { workspace = true }
+            "#,
+        )
+        .unwrap();
+        let deps = manifest.dependencies.as_ref().unwrap();
+        let package_name = |key: &str| deps[key].package_name(key).to_string();
+        assert_eq!(package_name("bar"), "bar");
+        assert_eq!(package_name("baz"), "real-baz");
+        assert_eq!(package_name("qux"), "qux");
+        assert_eq!(package_name("quux"), "quux");
+    }
+
+    #[test]
+    fn workspace_resolver_from_manifest() {
+        let resolver = |contents: &str| workspace_resolver(contents).unwrap();
+        assert_eq!(
+            resolver("[workspace]\nresolver = \"1\""),
+            Some(ResolveBehavior::V1)
+        );
+        assert_eq!(
+            resolver("[workspace]\nresolver = \"2\""),
+            Some(ResolveBehavior::V2)
+        );
+        assert_eq!(resolver("[workspace]\nmembers = [\"a\"]"), None);
+        assert_eq!(resolver("[package]\nname = \"foo\""), None);
+
+        let err = workspace_resolver("[workspace]\nresolver = \"3\"").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`resolver` setting `3` is not valid"));
+    }
+
+    #[test]
+    fn author_format() {
+        for valid in [
+            "Ferris",
+            "Jane Doe <jane@example.com>",
+            "  Jane <jane.doe+cargo@mail.example.org>  ",
+        ] {
+            assert!(is_valid_author(valid), "{}", valid);
+        }
+        for invalid in [
+            "foo <not-an-email>",
+            "<jane@example.com>",
+            "Jane <jane@localhost>",
+            "Jane <@example.com>",
+            "Jane <jane@example..com>",
+            "Jane <jane doe@example.com>",
+            "Jane <jane@example.com",
+            "Jane jane@example.com>",
+        ] {
+            assert!(!is_valid_author(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn profile_is_empty() {
+        let profile = |toml: &str| toml::from_str::<TomlProfile>(toml).unwrap();
+        assert!(TomlProfile::default().is_empty());
+        assert!(profile("").is_empty());
+        assert!(profile("package = {}").is_empty());
+        assert!(profile("build-override = {}\npackage.foo = {}").is_empty());
+
+        assert!(!profile("opt-level = 1").is_empty());
+        assert!(!profile("inherits = \"release\"").is_empty());
+        assert!(!profile("build-override.debug = true").is_empty());
+        assert!(!profile("package.foo.opt-level = 1").is_empty());
+    }
+}
@@ -15,11 +15,11 @@ use std::fs::{self, DirEntry};
 use std::path::{Path, PathBuf};
 
 use super::{
-    PathValue, StringOrBool, StringOrVec, TomlBenchTarget, TomlBinTarget, TomlExampleTarget,
+    PathValue, StringOrVec, TomlBenchTarget, TomlBinTarget, TomlBuild, TomlExampleTarget,
     TomlLibTarget, TomlManifest, TomlTarget, TomlTestTarget,
 };
 use crate::core::compiler::CrateType;
-use crate::core::{Edition, Feature, Features, Target};
+use crate::core::{Edition, Feature, Features, Target, TargetKind};
 use crate::util::errors::CargoResult;
 use crate::util::restricted_names;
 
@@ -36,13 +36,20 @@ pub fn targets(
     package_name: &str,
     package_root: &Path,
     edition: Edition,
-    custom_build: &Option<StringOrBool>,
+    custom_build: &Option<TomlBuild>,
     metabuild: &Option<StringOrVec>,
+    is_file: &dyn Fn(&Path) -> bool,
     warnings: &mut Vec<String>,
     errors: &mut Vec<String>,
 ) -> CargoResult<Vec<Target>> {
     let mut targets = Vec::new();
 
+    let package = manifest
+        .package
+        .as_ref()
+        .or_else(|| manifest.project.as_ref())
+        .ok_or_else(|| anyhow::format_err!("manifest has no `package` (or `project`)"))?;
+
     let has_lib;
 
     if let Some(target) = clean_lib(
@@ -50,6 +57,7 @@ pub fn targets(
         package_root,
         package_name,
         edition,
+        package.autolib,
         warnings,
     )? {
         targets.push(target);
@@ -58,12 +66,6 @@ pub fn targets(
         has_lib = false;
     }
 
-    let package = manifest
-        .package
-        .as_ref()
-        .or_else(|| manifest.project.as_ref())
-        .ok_or_else(|| anyhow::format_err!("manifest has no `package` (or `project`)"))?;
-
     targets.extend(clean_bins(
         features,
         manifest.bin.as_ref(),
@@ -103,8 +105,19 @@ pub fn targets(
         errors,
     )?);
 
+    validate_required_features(&targets, warnings);
+
     // processing the custom build script
-    if let Some(custom_build) = manifest.maybe_custom_build(custom_build, package_root) {
+    let custom_builds = manifest.maybe_custom_build(custom_build, package_root, is_file);
+    if custom_builds.len() > 1 {
+        warnings.push(format!(
+            "`build` lists {} build scripts, but only the first, `{}`, is run; \
+             running more than one build script per package is not supported yet",
+            custom_builds.len(),
+            custom_builds[0].display()
+        ));
+    }
+    if let Some(custom_build) = custom_builds.first() {
         if metabuild.is_some() {
             anyhow::bail!("cannot specify both `metabuild` and `build`");
         }
@@ -147,9 +160,20 @@ fn clean_lib(
     package_root: &Path,
     package_name: &str,
     edition: Edition,
+    autodiscover: Option<bool>,
     warnings: &mut Vec<String>,
 ) -> CargoResult<Option<Target>> {
-    let inferred = inferred_lib(package_root);
+    let inferred = if autodiscover == Some(false) {
+        if toml_lib.is_some() {
+            anyhow::bail!(
+                "`autolib = false` disables the library target, \
+                 but a `[lib]` section is also specified"
+            )
+        }
+        None
+    } else {
+        inferred_lib(package_root)
+    };
     let lib = match toml_lib {
         Some(lib) => {
             if let Some(ref name) = lib.name {
@@ -289,16 +313,7 @@ fn clean_bins(
 
         let name = bin.name();
 
-        if let Some(crate_types) = bin.crate_types() {
-            if !crate_types.is_empty() {
-                errors.push(format!(
-                    "the target `{}` is a binary and can't have any \
-                     crate-types set (currently \"{}\")",
-                    name,
-                    crate_types.join(", ")
-                ));
-            }
-        }
+        validate_no_crate_types(bin, "binary", warnings, errors);
 
         if bin.proc_macro() == Some(true) {
             errors.push(format!(
@@ -445,6 +460,7 @@ fn clean_tests(
 
     let mut result = Vec::new();
     for (path, toml) in targets {
+        validate_no_crate_types(&toml, "test", warnings, errors);
         let mut target =
             Target::test_target(&toml.name(), path, toml.required_features.clone(), edition);
         configure(&toml, &mut target)?;
@@ -499,6 +515,7 @@ fn clean_benches(
 
     let mut result = Vec::new();
     for (path, toml) in targets {
+        validate_no_crate_types(&toml, "benchmark", warnings, errors);
         let mut target =
             Target::bench_target(&toml.name(), path, toml.required_features.clone(), edition);
         configure(&toml, &mut target)?;
@@ -587,8 +604,57 @@ fn clean_targets_with_legacy_path(
     Ok(result)
 }
 
+/// The conventional location of a target's source file, without looking at
+/// the filesystem. See [`TomlTarget::effective_path`].
+///
+/// This is where the inference in [`targets`] would find the target if it
+/// were there: the binary named after the package is `src/main.rs`, and
+/// other binaries, examples, tests and benches are `<name>.rs` in their
+/// directory.
+pub(super) fn conventional_path(
+    package_root: &Path,
+    package_name: &str,
+    kind: &TargetKind,
+    name: Option<&str>,
+) -> Option<PathBuf> {
+    let name = match (kind, name) {
+        (TargetKind::Lib(_), _) => return Some(default_lib_path(package_root)),
+        (TargetKind::CustomBuild, _) => return Some(package_root.join("build.rs")),
+        (TargetKind::Bin, None) => package_name,
+        (_, Some(name)) => name,
+        (_, None) => return None,
+    };
+    if *kind == TargetKind::Bin && name == package_name {
+        return Some(default_main_path(package_root));
+    }
+    let dir = default_target_dir(package_root, kind)?;
+    Some(dir.join(format!("{}.rs", name)))
+}
+
+fn default_lib_path(package_root: &Path) -> PathBuf {
+    package_root.join("src").join("lib.rs")
+}
+
+fn default_main_path(package_root: &Path) -> PathBuf {
+    package_root.join("src").join("main.rs")
+}
+
+/// The directory whose entries are inferred as targets of `kind`.
+fn default_target_dir(package_root: &Path, kind: &TargetKind) -> Option<PathBuf> {
+    let dir = match kind {
+        TargetKind::Bin => package_root.join("src").join(DEFAULT_BIN_DIR_NAME),
+        TargetKind::ExampleLib(_) | TargetKind::ExampleBin => {
+            package_root.join(DEFAULT_EXAMPLE_DIR_NAME)
+        }
+        TargetKind::Test => package_root.join(DEFAULT_TEST_DIR_NAME),
+        TargetKind::Bench => package_root.join(DEFAULT_BENCH_DIR_NAME),
+        TargetKind::Lib(_) | TargetKind::CustomBuild => return None,
+    };
+    Some(dir)
+}
+
 fn inferred_lib(package_root: &Path) -> Option<PathBuf> {
-    let lib = package_root.join("src").join("lib.rs");
+    let lib = default_lib_path(package_root);
     if lib.exists() {
         Some(lib)
     } else {
@@ -597,7 +663,7 @@ fn inferred_lib(package_root: &Path) -> Option<PathBuf> {
 }
 
 fn inferred_bins(package_root: &Path, package_name: &str) -> Vec<(String, PathBuf)> {
-    let main = package_root.join("src").join("main.rs");
+    let main = default_main_path(package_root);
     let mut result = Vec::new();
     if main.exists() {
         result.push((package_name.to_string(), main));
@@ -687,9 +753,19 @@ fn toml_targets_and_inferred(
             }
 
             let mut rem_targets = vec![];
+            let mut shadowed = vec![];
             for target in inferred_targets {
-                if !seen_names.contains(&target.name) && !seen_paths.contains(&target_path(&target))
-                {
+                if seen_names.contains(&target.name) {
+                    // An explicit target with the same name but a different
+                    // path hides the discovered file.
+                    let explicit_path = targets
+                        .iter()
+                        .find(|t| t.name == target.name)
+                        .and_then(target_path);
+                    if explicit_path.map_or(false, |p| Some(p) != target_path(&target)) {
+                        shadowed.push(target);
+                    }
+                } else if !seen_paths.contains(&target_path(&target)) {
                     rem_targets.push(target);
                 }
             }
@@ -735,6 +811,19 @@ https://github.com/rust-lang/cargo/issues/5330",
             };
 
             if autodiscover {
+                for target in shadowed {
+                    warnings.push(format!(
+                        "{} target `{}` is specified explicitly in Cargo.toml, \
+                         so the automatically discovered file `{}` is ignored",
+                        target_kind_human,
+                        target.name(),
+                        target_path(&target)
+                            .as_deref()
+                            .and_then(|p| p.strip_prefix(package_root).ok())
+                            .unwrap_or_else(|| Path::new(""))
+                            .display(),
+                    ));
+                }
                 targets.append(&mut rem_targets);
             }
 
@@ -783,6 +872,28 @@ fn validate_target_name(
     Ok(())
 }
 
+/// Reports an error if a target other than a library or an example sets
+/// `crate-type`.
+fn validate_no_crate_types(
+    target: &TomlTarget,
+    target_kind_human: &str,
+    warnings: &mut Vec<String>,
+    errors: &mut Vec<String>,
+) {
+    target.validate_crate_types(target_kind_human, warnings);
+    if let Some(crate_types) = target.crate_types() {
+        if !crate_types.is_empty() {
+            errors.push(format!(
+                "the target `{}` is a {} and can't have any \
+                 crate-types set (currently \"{}\")",
+                target.name(),
+                target_kind_human,
+                crate_types.join(", ")
+            ));
+        }
+    }
+}
+
 /// Will check a list of toml targets, and make sure the target names are unique within a vector.
 fn validate_unique_names(targets: &[TomlTarget], target_kind: &str) -> CargoResult<()> {
     let mut seen = HashSet::new();
@@ -799,6 +910,52 @@ fn validate_unique_names(targets: &[TomlTarget], target_kind: &str) -> CargoResu
     Ok(())
 }
 
+/// Warns about `required-features` lists that are empty, repeat a feature, or
+/// list a feature alongside its negation.
+fn validate_required_features(targets: &[Target], warnings: &mut Vec<String>) {
+    for target in targets {
+        let required = match target.required_features() {
+            Some(required) => required,
+            None => continue,
+        };
+        let kind = target.kind().description();
+        if required.is_empty() {
+            warnings.push(format!(
+                "{} target `{}` has an empty `required-features` list, \
+                 which is the same as omitting it",
+                kind,
+                target.name()
+            ));
+            continue;
+        }
+        let mut seen = HashSet::new();
+        for feature in required {
+            if !seen.insert(feature.as_str()) {
+                warnings.push(format!(
+                    "{} target `{}` lists `{}` more than once in `required-features`",
+                    kind,
+                    target.name(),
+                    feature
+                ));
+            }
+        }
+        for feature in required {
+            if let Some(negated) = feature.strip_prefix('!') {
+                if seen.contains(negated) {
+                    warnings.push(format!(
+                        "{} target `{}` lists both `{}` and `{}` in `required-features`, \
+                         so it can never be built",
+                        kind,
+                        target.name(),
+                        negated,
+                        feature
+                    ));
+                }
+            }
+        }
+    }
+}
+
 fn configure(toml: &TomlTarget, target: &mut Target) -> CargoResult<()> {
     let t2 = target.clone();
     target
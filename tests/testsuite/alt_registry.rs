@@ -208,6 +208,7 @@ fn registry_and_path_dep_works() {
     p.cargo("build")
         .with_stderr(
             "\
+[WARNING] dependency (bar) specifies both `path` and `registry` without a `version`, `registry` is ignored
 [COMPILING] bar v0.0.1 ([CWD]/bar)
 [COMPILING] foo v0.0.1 ([CWD])
 [FINISHED] dev [unoptimized + debuginfo] target(s) in [..]s
@@ -216,6 +217,80 @@ fn registry_and_path_dep_works() {
         .run();
 }
 
+#[cargo_test]
+fn path_dep_with_registry_without_version() {
+    registry::alt_init();
+
+    for (key, value) in [
+        ("registry", "alternative"),
+        ("registry-index", "https://example.com/index"),
+    ] {
+        let p = project()
+            .file(
+                "Cargo.toml",
+                &format!(
+                    r#"
+                        [package]
+                        name = "foo"
+                        version = "0.0.1"
+
+                        [dependencies]
+                        bar = {{ path = "bar", {} = "{}" }}
+                    "#,
+                    key, value
+                ),
+            )
+            .file("src/lib.rs", "")
+            .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+            .file("bar/src/lib.rs", "")
+            .build();
+
+        p.cargo("check")
+            .with_stderr(&format!(
+                "\
+[WARNING] dependency (bar) specifies both `path` and `{key}` without a `version`, `{key}` is ignored
+[CHECKING] bar v0.0.1 ([CWD]/bar)
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+                key = key
+            ))
+            .run();
+    }
+}
+
+#[cargo_test]
+fn path_dep_with_registry_and_version() {
+    registry::alt_init();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = { path = "bar", version = "0.0.1", registry = "alternative" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_stderr(
+            "\
+[CHECKING] bar v0.0.1 ([CWD]/bar)
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn registry_incompatible_with_git() {
     registry::alt_init();
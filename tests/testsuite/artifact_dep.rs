@@ -119,6 +119,116 @@ Caused by:
     })
 }
 
+#[cargo_test]
+fn check_with_selected_binary_name() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.0"
+                authors = []
+                resolver = "2"
+
+                [dependencies]
+                bar = { path = "bar/", artifact = "bin:my-tool" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/bin/my-tool.rs", "fn main() {}")
+        .build();
+    p.cargo("check -Z bindeps")
+        .masquerade_as_nightly_cargo()
+        .with_stderr_does_not_contain("[WARNING] artifact dependency [..]")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.0"
+            authors = []
+            resolver = "2"
+
+            [dependencies]
+            bar = { path = "bar/", artifact = "bin:my tool" }
+        "#,
+    );
+    p.cargo("check -Z bindeps")
+        .masquerade_as_nightly_cargo()
+        .with_stderr_contains(
+            "\
+[WARNING] artifact dependency `bar` selects `bin:my tool`, which can never match a binary \
+target: invalid character ` ` in binary name: `my tool`, [..]",
+        )
+        .with_status(101)
+        .run();
+}
+
+#[cargo_test]
+fn lib_true_with_library_only_artifact() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.0"
+                authors = []
+                resolver = "2"
+
+                [dependencies]
+                bar = { path = "bar/", artifact = "bin", lib = true }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.5.0"
+                authors = []
+
+                [lib]
+                crate-type = ["rlib", "cdylib"]
+            "#,
+        )
+        .file("bar/src/lib.rs", "")
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+    p.cargo("check -Z bindeps")
+        .masquerade_as_nightly_cargo()
+        .with_stderr_does_not_contain("[WARNING] artifact dependency [..]")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.0"
+            authors = []
+            resolver = "2"
+
+            [dependencies]
+            bar = { path = "bar/", artifact = "cdylib", lib = true }
+        "#,
+    );
+    p.cargo("check -Z bindeps")
+        .masquerade_as_nightly_cargo()
+        .with_stderr_contains(
+            "\
+[WARNING] artifact dependency `bar` sets `lib = true` alongside only library artifacts \
+(`cdylib`), which also links the library as a Rust crate and only works if its `[lib]` \
+builds an `rlib` too. Remove `lib = true` if only the artifact is needed.",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn check_with_invalid_target_triple() {
     // invalid name
@@ -417,6 +417,70 @@ Caused by:
         .run();
 }
 
+#[cargo_test]
+fn cargo_compile_with_test_and_crate_type() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                authors = []
+                version = "0.0.0"
+
+                [[test]]
+                name = "the_foo_test"
+                path = "tests/foo.rs"
+                crate-type = ["cdylib"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("tests/foo.rs", "")
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  the target `the_foo_test` is a test and can't have any crate-types set \
+(currently \"cdylib\")",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn cargo_compile_with_example_and_crate_type() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                authors = []
+                version = "0.0.0"
+
+                [[example]]
+                name = "plugin"
+                crate-type = ["cdylib"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("examples/plugin.rs", "")
+        .build();
+
+    p.cargo("build --example plugin")
+        .with_stderr(
+            "\
+[COMPILING] foo v0.0.0 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn cargo_compile_api_exposes_artifact_paths() {
     let p = project()
@@ -1020,6 +1084,40 @@ error: failed to parse manifest at `[..]`
 
 Caused by:
   invalid character ` ` in dependency name: `haha this isn't a valid name 🐛`, characters must be Unicode XID characters (numbers, `-`, `_`, or most letters)
+  The dependency key only needs to be a valid name since the package is already set with `package = \"libc\"`, for example:
+  haha_this_isn_t_a_valid_name__ = { package = \"libc\", ... }
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn cargo_compile_with_invalid_dep_name() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "buggin"
+                version = "0.1.0"
+
+                [dependencies]
+                "1libc" = "0.1"
+            "#,
+        )
+        .file("src/main.rs", &main_file(r#""What's good?""#, &[]))
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+error: failed to parse manifest at `[..]`
+
+Caused by:
+  the name `1libc` cannot be used as a dependency name, the name cannot start with a digit
+  If `1libc` is the name of the package to depend on, use a valid dependency key and set the package name with `package = \"1libc\"`, for example:
+  _1libc = { package = \"1libc\", ... }
 ",
         )
         .run();
@@ -1710,7 +1808,8 @@ fn dev_dependencies_conflicting_warning() {
     p.cargo("build")
         .with_stderr_contains(
 "[WARNING] conflicting between `dev-dependencies` and `dev_dependencies` in the `foo` package.\n
-        `dev_dependencies` is ignored and not recommended for use in the future"
+        `dev_dependencies` is ignored and not recommended for use in the future
+        `dev_dependencies` is declared on line 9 of Cargo.toml"
         )
         .run();
 }
@@ -1746,7 +1845,8 @@ fn build_dependencies_conflicting_warning() {
     p.cargo("build")
         .with_stderr_contains(
 "[WARNING] conflicting between `build-dependencies` and `build_dependencies` in the `foo` package.\n
-        `build_dependencies` is ignored and not recommended for use in the future"
+        `build_dependencies` is ignored and not recommended for use in the future
+        `build_dependencies` is declared on line 9 of Cargo.toml"
         )
         .run();
 }
@@ -3025,7 +3125,8 @@ fn cargo_platform_specific_dependency_build_dependencies_conflicting_warning() {
     p.cargo("build")
         .with_stderr_contains(
         format!("[WARNING] conflicting between `build-dependencies` and `build_dependencies` in the `{}` platform target.\n
-        `build_dependencies` is ignored and not recommended for use in the future", host)
+        `build_dependencies` is ignored and not recommended for use in the future
+        `build_dependencies` is declared on line 10 of Cargo.toml", host)
         )
         .run();
 
@@ -3065,7 +3166,8 @@ fn cargo_platform_specific_dependency_dev_dependencies_conflicting_warning() {
     p.cargo("build")
         .with_stderr_contains(
         format!("[WARNING] conflicting between `dev-dependencies` and `dev_dependencies` in the `{}` platform target.\n
-        `dev_dependencies` is ignored and not recommended for use in the future", host)
+        `dev_dependencies` is ignored and not recommended for use in the future
+        `dev_dependencies` is declared on line 9 of Cargo.toml", host)
         )
         .run();
 
@@ -5792,6 +5894,102 @@ fn build_with_no_lib() {
         .run();
 }
 
+#[cargo_test]
+fn explicit_bin_shadows_discovered_bin() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2018"
+
+                [[bin]]
+                name = "tool"
+                path = "tools/tool.rs"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("src/bin/tool.rs", "fn main() { println!(\"discovered\"); }")
+        .file("tools/tool.rs", "fn main() { println!(\"explicit\"); }")
+        .build();
+
+    p.cargo("run --bin tool")
+        .with_stdout("explicit")
+        .with_stderr(
+            "\
+[WARNING] binary target `tool` is specified explicitly in Cargo.toml, \
+so the automatically discovered file `src/bin/tool.rs` is ignored
+[COMPILING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+[RUNNING] `target/debug/tool[EXE]`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn autolib_disables() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                autolib = false
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file("src/lib.rs", "compile_error!(\"not a target\");")
+        .build();
+
+    p.cargo("build")
+        .with_stderr(
+            "\
+[COMPILING] foo v0.0.1 ([CWD])
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+    p.cargo("build --lib")
+        .with_status(101)
+        .with_stderr("[ERROR] no library targets found in package `foo`")
+        .run();
+}
+
+#[cargo_test]
+fn autolib_conflicts_with_lib_section() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                autolib = false
+
+                [lib]
+                path = "src/lib.rs"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  `autolib = false` disables the library target, but a `[lib]` section is also specified
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn build_with_relative_cargo_home_path() {
     let p = project()
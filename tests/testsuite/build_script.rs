@@ -1,5 +1,6 @@
 //! Tests for build.rs scripts.
 
+use super::read_manifest::with_workspace;
 use cargo_test_support::compare::assert_match_exact;
 use cargo_test_support::paths::CargoPathExt;
 use cargo_test_support::registry::Package;
@@ -836,6 +837,97 @@ not have a custom build script
         .run();
 }
 
+#[cargo_test]
+fn links_valid_value() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [project]
+                name = "foo"
+                version = "0.5.0"
+                authors = []
+                links = "openssl"
+                build = "build.rs"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build").run();
+}
+
+#[cargo_test]
+fn links_invalid_value() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [project]
+                name = "foo"
+                version = "0.5.0"
+                authors = []
+                links = "open ssl"
+                build = "build.rs"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]/foo/Cargo.toml`
+
+Caused by:
+  invalid character ` ` in `links` value `open ssl`, \
+characters must be letters, numbers, `-`, or `_`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn links_invalid_value_from_registry() {
+    // Only local packages have their `links` value checked.
+    Package::new("bar", "0.5.0")
+        .links("open ssl")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.5.0"
+                links = "open ssl"
+                build = "build.rs"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [project]
+                name = "foo"
+                version = "0.5.0"
+                authors = []
+
+                [dependencies]
+                bar = "0.5.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build").run();
+}
+
 #[cargo_test]
 fn links_duplicates() {
     // this tests that the links_duplicates are caught at resolver time
@@ -3827,8 +3919,6 @@ fn output_shows_on_vv() {
 
 #[cargo_test]
 fn links_with_dots() {
-    let target = rustc_host();
-
     let p = project()
         .file(
             "Cargo.toml",
@@ -3842,28 +3932,20 @@ fn links_with_dots() {
             "#,
         )
         .file("src/lib.rs", "")
-        .file(
-            "build.rs",
-            r#"
-                fn main() {
-                    println!("cargo:rustc-link-search=bar")
-                }
-            "#,
-        )
-        .file(
-            ".cargo/config",
-            &format!(
-                r#"
-                    [target.{}.'a.b']
-                    rustc-link-search = ["foo"]
-                "#,
-                target
-            ),
-        )
+        .file("build.rs", "fn main() {}")
         .build();
 
-    p.cargo("build -v")
-        .with_stderr_contains("[RUNNING] `rustc --crate-name foo [..] [..] -L foo[..]`")
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]/foo/Cargo.toml`
+
+Caused by:
+  invalid character `.` in `links` value `a.b`, \
+characters must be letters, numbers, `-`, or `_`
+",
+        )
         .run();
 }
 
@@ -4903,3 +4985,216 @@ for more information about build script outputs.
         )
         .run();
 }
+
+#[cargo_test]
+fn manifest_custom_build_path() {
+    let p = project()
+        .file("Cargo.toml", &basic_manifest("foo", "0.0.1"))
+        .file("src/lib.rs", "")
+        .build();
+    let custom_build = |p: &cargo_test_support::Project| {
+        with_workspace(p.root().join("Cargo.toml"), |ws| {
+            let manifest = ws.current().unwrap().manifest();
+            (
+                manifest.has_custom_build(),
+                manifest.custom_build_path().map(|p| p.to_path_buf()),
+            )
+        })
+    };
+
+    // No build script at all.
+    assert_eq!(custom_build(&p), (false, None));
+
+    // An implicit `build.rs` next to `Cargo.toml`.
+    p.change_file("build.rs", "fn main() {}");
+    assert_eq!(custom_build(&p), (true, Some(p.root().join("build.rs"))));
+
+    // `build = false` turns off the implicit `build.rs`.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            build = false
+        "#,
+    );
+    assert_eq!(custom_build(&p), (false, None));
+
+    // An explicit path.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            build = "scripts/gen.rs"
+        "#,
+    );
+    assert_eq!(
+        custom_build(&p),
+        (true, Some(p.root().join("scripts/gen.rs")))
+    );
+}
+
+#[cargo_test]
+fn multiple_build_scripts() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["multiple-build-scripts"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                build = ["build/gen.rs"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build/gen.rs", "fn main() {}")
+        .build();
+
+    // A single entry behaves like `build = "build/gen.rs"`.
+    p.cargo("check -v")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[COMPILING] foo v0.0.1 ([CWD])
+[RUNNING] `rustc --crate-name build_script_gen build/gen.rs [..]`
+[RUNNING] `[..]/build-script-gen`
+[RUNNING] `rustc --crate-name foo [..]`
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    // An empty list turns off build scripts like `build = false`.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["multiple-build-scripts"]
+
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            build = []
+        "#,
+    );
+    p.change_file("build.rs", r#"fn main() { panic!("should not run") }"#);
+    p.cargo("check -v")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[CHECKING] foo v0.0.1 ([CWD])
+[RUNNING] `rustc --crate-name foo [..]`
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    // Every entry is recorded, but only the first one is run so far.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["multiple-build-scripts"]
+
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            build = ["build/gen.rs", "build/link.rs"]
+        "#,
+    );
+    p.change_file("build/link.rs", "fn main() {}");
+    let build_scripts = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        ws.current().unwrap().manifest().build_scripts().to_vec()
+    });
+    assert_eq!(
+        build_scripts,
+        [
+            p.root().join("build/gen.rs"),
+            p.root().join("build/link.rs")
+        ]
+    );
+    p.cargo("check -v")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[WARNING] `build` lists 2 build scripts, but only the first, `build/gen.rs`, is run; \
+running more than one build script per package is not supported yet
+[COMPILING] foo v0.0.1 ([CWD])
+[RUNNING] `[..]/build-script-gen`
+[RUNNING] `rustc --crate-name foo [..]`
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn multiple_build_scripts_gated() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                build = ["build/gen.rs"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build/gen.rs", "fn main() {}")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  feature `multiple-build-scripts` is required
+
+  The package requires the Cargo feature called `multiple-build-scripts`, \
+  but that feature is not stabilized in this version of Cargo (1.[..]).
+  Consider adding `cargo-features = [\"multiple-build-scripts\"]` to the top of Cargo.toml \
+  (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#multiple-build-scripts \
+  for more information about the status of this feature.
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn multiple_build_scripts_invalid() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["multiple-build-scripts"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                build = ["build/gen.rs", "build/data.txt"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  `build` entry `build/data.txt` is not a path to a `.rs` file
+",
+        )
+        .run();
+}
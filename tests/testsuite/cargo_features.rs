@@ -215,6 +215,36 @@ release and is no longer necessary to be listed in the manifest
         .run();
 }
 
+#[cargo_test]
+fn duplicate_feature_warns() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["test-dummy-unstable", "test-dummy-unstable"]
+
+                [package]
+                name = "a"
+                version = "0.0.1"
+                authors = []
+                im-a-teapot = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+warning: the cargo feature `test-dummy-unstable` is listed more than once \
+in `cargo-features`
+[COMPILING] a [..]
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn allow_features() {
     if !is_nightly() {
@@ -374,7 +374,7 @@ lto = false
             opt_level: Some(toml::TomlOptLevel("s".to_string())),
             lto: Some(toml::StringOrBool::Bool(true)),
             codegen_units: Some(5),
-            debug: Some(toml::U32OrBool::Bool(true)),
+            debug: Some(toml::TomlDebugInfo::Bool(true)),
             debug_assertions: Some(true),
             rpath: Some(true),
             panic: Some("abort".to_string()),
@@ -417,7 +417,7 @@ fn profile_env_var_prefix() {
         .build();
     let p: toml::TomlProfile = config.get("profile.dev").unwrap();
     assert_eq!(p.debug_assertions, None);
-    assert_eq!(p.debug, Some(toml::U32OrBool::U32(1)));
+    assert_eq!(p.debug, Some(toml::TomlDebugInfo::Level(1)));
 
     let config = ConfigBuilder::new()
         .env("CARGO_PROFILE_DEV_DEBUG_ASSERTIONS", "false")
@@ -425,7 +425,7 @@ fn profile_env_var_prefix() {
         .build();
     let p: toml::TomlProfile = config.get("profile.dev").unwrap();
     assert_eq!(p.debug_assertions, Some(false));
-    assert_eq!(p.debug, Some(toml::U32OrBool::U32(1)));
+    assert_eq!(p.debug, Some(toml::TomlDebugInfo::Level(1)));
 }
 
 #[cargo_test]
@@ -1485,7 +1485,7 @@ fn all_profile_options() {
         lto: Some(toml::StringOrBool::String("thin".to_string())),
         codegen_backend: Some(InternedString::new("example")),
         codegen_units: Some(123),
-        debug: Some(toml::U32OrBool::U32(1)),
+        debug: Some(toml::TomlDebugInfo::Level(1)),
         split_debuginfo: Some("packed".to_string()),
         debug_assertions: Some(true),
         rpath: Some(true),
@@ -225,6 +225,102 @@ fn per_crate_target_test(
     }
 }
 
+#[cargo_test]
+fn per_crate_default_target_host() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    cargo-features = ["per-package-target"]
+
+                    [package]
+                    name = "foo"
+                    version = "0.0.0"
+                    authors = []
+                    default-target = "{}"
+                "#,
+                rustc_host()
+            ),
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build").masquerade_as_nightly_cargo().run();
+}
+
+#[cargo_test]
+fn per_crate_default_target_malformed() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["per-package-target"]
+
+                [package]
+                name = "foo"
+                version = "0.0.0"
+                authors = []
+                default-target = "x64-linux"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("metadata --format-version=1")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  `package.default-target` value `x64-linux` is not a valid target triple
+  Built-in target triples are listed by `rustc --print target-list`, \
+or the value may be a path to a custom target specification ending in `.json`.
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn per_crate_forced_target_missing_json() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["per-package-target"]
+
+                [package]
+                name = "foo"
+                version = "0.0.0"
+                authors = []
+                forced-target = "missing.json"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  invalid `package.forced-target` value `missing.json`
+
+Caused by:
+  target path \"missing.json\" is not a valid file
+
+Caused by:
+  [..]
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn per_crate_default_target_is_default() {
     per_crate_target_test(Some(cross_compile::alternate()), None, None);
@@ -1,8 +1,9 @@
 //! Tests for `[features]` table.
 
+use super::read_manifest::with_workspace;
 use cargo_test_support::paths::CargoPathExt;
 use cargo_test_support::registry::{Dependency, Package};
-use cargo_test_support::{basic_manifest, project};
+use cargo_test_support::{basic_manifest, project, rustc_host};
 
 #[cargo_test]
 fn invalid1() {
@@ -78,6 +79,68 @@ foo v0.0.1 ([..]) []
             "\
 foo v0.0.1 ([..]) [bar,baz]
 └── bar v1.0.0 ([..]) []
+",
+        )
+        .run();
+
+    p.change_file("src/main.rs", "fn main() {}");
+    p.cargo("check")
+        .with_stderr(
+            "\
+[WARNING] feature `bar` has the same name as the non-optional dependency `bar`; \
+enabling the feature does not affect the dependency, \
+consider renaming the feature or making the dependency optional
+[CHECKING] bar v1.0.0 ([..])
+[CHECKING] foo v0.0.1 ([..])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn same_name_as_optional_dep() {
+    // Optional dependencies share their name with a feature, so defining
+    // that feature explicitly is fine.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [features]
+                bar = ["dep:bar", "baz"]
+                baz = []
+
+                [dependencies.bar]
+                path = "bar"
+                optional = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "1.0.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("tree --features bar -f")
+        .arg("{p} [{f}]")
+        .with_stderr("")
+        .with_stdout(
+            "\
+foo v0.0.1 ([..]) [bar,baz]
+└── bar v1.0.0 ([..]) []
+",
+        )
+        .run();
+
+    p.cargo("check --features bar")
+        .with_stderr(
+            "\
+[CHECKING] bar v1.0.0 ([..])
+[CHECKING] foo v0.0.1 ([..])
+[FINISHED] [..]
 ",
         )
         .run();
@@ -194,6 +257,41 @@ Caused by:
         .run();
 }
 
+#[cargo_test]
+fn invalid5_target_dev_dependency() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [project]
+                    name = "foo"
+                    version = "0.0.1"
+                    authors = []
+
+                    [target.{}.dev-dependencies.bar]
+                    path = "bar"
+                    optional = true
+                "#,
+                rustc_host()
+            ),
+        )
+        .file("src/main.rs", "")
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  dev-dependencies are not allowed to be optional: `bar`
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn invalid6() {
     let p = project()
@@ -2077,3 +2175,73 @@ fn default_features_conflicting_warning() {
         )
         .run();
 }
+
+#[cargo_test]
+fn default_features_contradicting_error() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                authors = []
+
+                [dependencies]
+                a = { path = "a", default-features = false, default_features = true }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "")
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  dependency (a) specifies both `default-features = false` and `default_features = true`, \
+which contradict each other. \
+Remove `default_features`, it is a deprecated spelling of `default-features`.
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn optional_dependencies_listed() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                required = "1.0"
+                renamed = { package = "original", version = "1.0", optional = true }
+
+                [target.'cfg(unix)'.dependencies]
+                unix-only = { version = "1.0", optional = true }
+
+                [dev-dependencies]
+                dev = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let optional = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        let manifest = ws.current().unwrap().manifest();
+        manifest
+            .optional_dependencies()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>()
+    });
+    assert_eq!(optional, ["renamed", "unix-only"]);
+}
@@ -3510,3 +3510,68 @@ fn git_with_force_push() {
     amend_commit("awesome-four");
     verify("awesome-four");
 }
+
+#[cargo_test]
+fn rev_that_looks_like_a_branch() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.0"
+
+                [dependencies.bar]
+                git = "http://127.0.0.1"
+                rev = "main"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains(
+            "\
+[WARNING] `rev` of dependency (bar) is `main`, which does not look like a commit hash. \
+If it names a branch or tag, use `branch = \"main\"` or `tag = \"main\"` instead.
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn rev_full_hash_no_warning() {
+    let git_project = git::new("bar", |project| {
+        project
+            .file("Cargo.toml", &basic_lib_manifest("bar"))
+            .file("src/bar.rs", "")
+    });
+    let repo = git2::Repository::open(&git_project.root()).unwrap();
+    let head = repo.head().unwrap().target().unwrap().to_string();
+    assert_eq!(head.len(), 40);
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.0.0"
+
+                    [dependencies.bar]
+                    git = '{}'
+                    rev = "{}"
+                "#,
+                git_project.url(),
+                head
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_stderr_does_not_contain("[WARNING][..]")
+        .run();
+}
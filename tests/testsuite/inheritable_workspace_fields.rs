@@ -108,6 +108,36 @@ Caused by:
         .run();
 }
 
+#[cargo_test]
+fn warn_misspelled_workspace_package_field() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.package]
+            descripton = "This is a crate"
+            not-even-close = true
+            "#,
+        )
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    p.cargo("build")
+        .with_stderr(
+            "\
+[WARNING] [CWD]/Cargo.toml: unused manifest key: workspace.package.descripton, \
+did you mean `workspace.package.description`?
+[WARNING] [CWD]/Cargo.toml: unused manifest key: workspace.package.not-even-close
+[COMPILING] bar v0.1.0 ([CWD]/bar)
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn inherit_own_workspace_fields() {
     registry::init();
@@ -528,36 +558,23 @@ Caused by:
 }
 
 #[cargo_test]
-fn inherited_dependencies_union_features() {
-    Package::new("dep", "0.1.0")
-        .feature("fancy", &["fancy_dep"])
-        .feature("dancy", &["dancy_dep"])
-        .add_dep(Dependency::new("fancy_dep", "0.2").optional(true))
-        .add_dep(Dependency::new("dancy_dep", "0.6").optional(true))
-        .file("src/lib.rs", "")
-        .publish();
-
-    Package::new("fancy_dep", "0.2.4").publish();
-    Package::new("dancy_dep", "0.6.8").publish();
-
+fn warn_duplicate_inherited_authors() {
     let p = project()
         .file(
             "Cargo.toml",
             r#"
             cargo-features = ["workspace-inheritance"]
 
-            [project]
-            name = "bar"
-            version = "0.2.0"
-            authors = []
-            [dependencies]
-            dep = { workspace = true, features = ["dancy"] }
+            [package]
+            name = "foo"
+            version = "1.2.5"
+            authors.workspace = true
 
             [workspace]
             members = []
-            [workspace.dependencies]
-            dep = { version = "0.1", features = ["fancy"] }
-        "#,
+            [workspace.package]
+            authors = ["Rustaceans", "Ferris", "Rustaceans"]
+            "#,
         )
         .file("src/main.rs", "fn main() {}")
         .build();
@@ -566,395 +583,1540 @@ fn inherited_dependencies_union_features() {
         .masquerade_as_nightly_cargo()
         .with_stderr(
             "\
-[UPDATING] `[..]` index
-[DOWNLOADING] crates ...
-[DOWNLOADED] fancy_dep v0.2.4 ([..])
-[DOWNLOADED] dep v0.1.0 ([..])
-[DOWNLOADED] dancy_dep v0.6.8 ([..])
-[COMPILING] [..]
-[COMPILING] [..]
-[COMPILING] dep v0.1.0
-[COMPILING] bar v0.2.0 ([CWD])
-[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+[WARNING] [CWD]/Cargo.toml: duplicate author `Rustaceans` in `package.authors` is ignored
+[COMPILING] foo v1.2.5 ([CWD])
+[FINISHED] [..]
 ",
         )
         .run();
-
-    let lockfile = p.read_lockfile();
-    assert!(lockfile.contains("dep"));
-    assert!(lockfile.contains("fancy_dep"));
-    assert!(lockfile.contains("dancy_dep"));
+    p.cargo("read-manifest")
+        .masquerade_as_nightly_cargo()
+        .with_stdout_contains(r#"[..]"authors":["Rustaceans","Ferris"][..]"#)
+        .run();
 }
 
 #[cargo_test]
-fn inherit_workspace_fields() {
-    registry::init();
-
-    let p = project().build();
-
-    let _ = git::repo(&paths::root().join("foo"))
+fn lint_author_emails() {
+    let p = project()
         .file(
             "Cargo.toml",
             r#"
+            cargo-features = ["workspace-inheritance", "lint-author-emails"]
+
+            [package]
+            name = "foo"
+            version = "1.2.5"
+            authors.workspace = true
+
             [workspace]
-            members = ["bar"]
+            members = []
             [workspace.package]
-            version = "1.2.3"
-            authors = ["Rustaceans"]
-            description = "This is a crate"
-            documentation = "https://www.rust-lang.org/learn"
-            readme = "README.md"
-            homepage = "https://www.rust-lang.org"
-            repository = "https://github.com/example/example"
-            license = "MIT"
-            license-file = "LICENSE"
-            keywords = ["cli"]
-            categories = ["development-tools"]
-            publish = true
-            edition = "2018"
-            rust-version = "1.60"
-            exclude = ["foo.txt"]
-            include = ["bar.txt", "**/*.rs", "Cargo.toml", "LICENSE", "README.md"]
-            [workspace.package.badges]
-            gitlab = { repository = "https://gitlab.com/rust-lang/rust", branch = "master" }
+            authors = ["Ferris <ferris@rust-lang.org>", "Rustaceans", "foo <not-an-email>"]
             "#,
         )
         .file("src/main.rs", "fn main() {}")
-        .file(
-            "bar/Cargo.toml",
-            r#"
-            badges.workspace = true
-            cargo-features = ["workspace-inheritance"]
-            [package]
-            name = "bar"
-            workspace = ".."
-            version.workspace = true
-            authors.workspace = true
-            description.workspace = true
-            documentation.workspace = true
-            readme.workspace = true
-            homepage.workspace = true
-            repository.workspace = true
-            license.workspace = true
-            license-file.workspace = true
-            keywords.workspace = true
-            categories.workspace = true
-            publish.workspace = true
-            edition.workspace = true
-            rust-version.workspace = true
-            exclude.workspace = true
-            include.workspace = true
-        "#,
-        )
-        .file("LICENSE", "license")
-        .file("README.md", "README.md")
-        .file("bar/src/main.rs", "fn main() {}")
-        .file("bar/foo.txt", "") // should be ignored when packaging
-        .file("bar/bar.txt", "") // should be included when packaging
         .build();
 
-    p.cargo("publish --token sekrit")
+    p.cargo("check")
         .masquerade_as_nightly_cargo()
-        .cwd("bar")
+        .with_stderr(
+            "\
+[WARNING] [CWD]/Cargo.toml: `package.authors` entry `foo <not-an-email>` should be a name, \
+optionally followed by an email address in angle brackets, like `Jane Doe <jane@example.com>`
+[CHECKING] foo v1.2.5 ([CWD])
+[FINISHED] [..]
+",
+        )
         .run();
-    publish::validate_upload_with_contents(
+
+    // Packages that are never published are left alone.
+    p.change_file(
+        "Cargo.toml",
         r#"
-        {
-          "authors": ["Rustaceans"],
-          "badges": {
-            "gitlab": { "branch": "master", "repository": "https://gitlab.com/rust-lang/rust" }
-          },
-          "categories": ["development-tools"],
-          "deps": [],
-          "description": "This is a crate",
-          "documentation": "https://www.rust-lang.org/learn",
-          "features": {},
-          "homepage": "https://www.rust-lang.org",
-          "keywords": ["cli"],
-          "license": "MIT",
-          "license_file": "../LICENSE",
-          "links": null,
-          "name": "bar",
-          "readme": "README.md",
-          "readme_file": "../README.md",
-          "repository": "https://github.com/example/example",
-          "vers": "1.2.3"
-          }
+        cargo-features = ["workspace-inheritance", "lint-author-emails"]
+
+        [package]
+        name = "foo"
+        version = "1.2.5"
+        authors.workspace = true
+        publish = false
+
+        [workspace]
+        members = []
+        [workspace.package]
+        authors = ["foo <not-an-email>"]
         "#,
-        "bar-1.2.3.crate",
-        &[
-            "Cargo.lock",
-            "Cargo.toml",
-            "Cargo.toml.orig",
-            "src/main.rs",
-            "README.md",
-            "LICENSE",
-            ".cargo_vcs_info.json",
-            "bar.txt",
-        ],
-        &[(
-            "Cargo.toml",
-            &format!(
-                r#"{}
-cargo-features = ["workspace-inheritance"]
-
-[package]
-edition = "2018"
-rust-version = "1.60"
-name = "bar"
-version = "1.2.3"
-authors = ["Rustaceans"]
-exclude = ["foo.txt"]
-include = [
-    "bar.txt",
-    "**/*.rs",
-    "Cargo.toml",
-    "LICENSE",
-    "README.md",
-]
-publish = true
-description = "This is a crate"
-homepage = "https://www.rust-lang.org"
-documentation = "https://www.rust-lang.org/learn"
-readme = "README.md"
-keywords = ["cli"]
-categories = ["development-tools"]
-license = "MIT"
-license-file = "LICENSE"
-repository = "https://github.com/example/example"
-
-[badges.gitlab]
-branch = "master"
-repository = "https://gitlab.com/rust-lang/rust"
-"#,
-                cargo::core::package::MANIFEST_PREAMBLE
-            ),
-        )],
     );
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[CHECKING] foo v1.2.5 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
 }
 
 #[cargo_test]
-fn inherit_dependencies() {
+fn warn_inherited_homepage_without_scheme() {
     let p = project()
         .file(
             "Cargo.toml",
             r#"
-            [workspace]
-            members = ["bar"]
-            [workspace.dependencies]
-            dep = "0.1"
-            dep-build = "0.8"
-            dep-dev = "0.5.2"
-        "#,
-        )
-        .file(
-            "bar/Cargo.toml",
-            r#"
-            cargo-features = ["workspace-inheritance"]
+            cargo-features = ["workspace-inheritance", "lint-metadata-urls"]
 
-            [project]
-            workspace = ".."
-            name = "bar"
-            version = "0.2.0"
-            authors = []
-            [dependencies]
-            dep.workspace = true
-            [build-dependencies]
-            dep-build.workspace = true
-            [dev-dependencies]
-            dep-dev.workspace = true
-        "#,
+            [package]
+            name = "foo"
+            version = "1.2.5"
+            homepage.workspace = true
+
+            [workspace]
+            members = []
+            [workspace.package]
+            homepage = "example.com"
+            "#,
         )
-        .file("bar/src/main.rs", "fn main() {}")
+        .file("src/main.rs", "fn main() {}")
         .build();
 
-    Package::new("dep", "0.1.2").publish();
-    Package::new("dep-build", "0.8.2").publish();
-    Package::new("dep-dev", "0.5.2").publish();
-
     p.cargo("build")
-        .masquerade_as_nightly_cargo()
         .masquerade_as_nightly_cargo()
         .with_stderr(
             "\
-[UPDATING] `[..]` index
-[DOWNLOADING] crates ...
-[DOWNLOADED] dep-build v0.8.2 ([..])
-[DOWNLOADED] dep v0.1.2 ([..])
-[COMPILING] dep v0.1.2
-[COMPILING] bar v0.2.0 ([CWD]/bar)
-[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+[WARNING] [CWD]/Cargo.toml: `package.homepage` value `example.com` is not an absolute URL, \
+consider adding a scheme such as `https://`
+[COMPILING] foo v1.2.5 ([CWD])
+[FINISHED] [..]
 ",
         )
         .run();
+}
 
-    p.cargo("check").masquerade_as_nightly_cargo().run();
-    let lockfile = p.read_lockfile();
-    assert!(lockfile.contains("dep"));
-    assert!(lockfile.contains("dep-dev"));
-    assert!(lockfile.contains("dep-build"));
-    p.cargo("publish --token sekrit")
+#[cargo_test]
+fn inherit_homepage_with_scheme() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance", "lint-metadata-urls"]
+
+            [package]
+            name = "foo"
+            version = "1.2.5"
+            homepage.workspace = true
+
+            [workspace]
+            members = []
+            [workspace.package]
+            homepage = "https://example.com"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
         .masquerade_as_nightly_cargo()
-        .cwd("bar")
+        .with_stderr(
+            "\
+[COMPILING] foo v1.2.5 ([CWD])
+[FINISHED] [..]
+",
+        )
         .run();
-    publish::validate_upload_with_contents(
-        r#"
-        {
-          "authors": [],
-          "badges": {},
-          "categories": [],
-          "deps": [
-            {
-              "default_features": true,
-              "features": [],
-              "kind": "normal",
-              "name": "dep",
-              "optional": false,
-              "registry": "https://github.com/rust-lang/crates.io-index",
-              "target": null,
-              "version_req": "^0.1"
-            },
-            {
-              "default_features": true,
-              "features": [],
-              "kind": "dev",
-              "name": "dep-dev",
-              "optional": false,
-              "registry": "https://github.com/rust-lang/crates.io-index",
-              "target": null,
-              "version_req": "^0.5.2"
-            },
-            {
-              "default_features": true,
-              "features": [],
-              "kind": "build",
-              "name": "dep-build",
-              "optional": false,
-              "registry": "https://github.com/rust-lang/crates.io-index",
-              "target": null,
-              "version_req": "^0.8"
-            }
-          ],
-          "description": null,
-          "documentation": null,
-          "features": {},
-          "homepage": null,
-          "keywords": [],
-          "license": null,
-          "license_file": null,
-          "links": null,
-          "name": "bar",
-          "readme": null,
-          "readme_file": null,
-          "repository": null,
-          "vers": "0.2.0"
-          }
-        "#,
-        "bar-0.2.0.crate",
-        &["Cargo.toml", "Cargo.toml.orig", "Cargo.lock", "src/main.rs"],
-        &[(
+}
+
+#[cargo_test]
+fn warn_inherited_maintenance_badge_unknown_status() {
+    let p = project()
+        .file(
             "Cargo.toml",
-            &format!(
-                r#"{}
-cargo-features = ["workspace-inheritance"]
+            r#"
+            cargo-features = ["workspace-inheritance"]
+            badges.workspace = true
 
-[package]
-name = "bar"
-version = "0.2.0"
-authors = []
+            [package]
+            name = "foo"
+            version = "1.2.5"
 
-[dependencies.dep]
-version = "0.1"
+            [workspace]
+            members = []
+            [workspace.package.badges]
+            maintenance = { status = "abandoned" }
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
 
-[dev-dependencies.dep-dev]
-version = "0.5.2"
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[WARNING] [CWD]/Cargo.toml: `badges.maintenance.status` is `abandoned`, expected one of \
+`actively-developed`, `passively-maintained`, `as-is`, `experimental`, \
+`looking-for-maintainer`, `deprecated`, `none`
+[COMPILING] foo v1.2.5 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
 
-[build-dependencies.dep-build]
-version = "0.8"
-"#,
-                cargo::core::package::MANIFEST_PREAMBLE
-            ),
-        )],
-    );
+#[cargo_test]
+fn maintenance_badge_known_status() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "1.2.5"
+
+            [badges]
+            maintenance = { status = "passively-maintained" }
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .with_stderr(
+            "\
+[COMPILING] foo v1.2.5 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
 }
 
 #[cargo_test]
-fn inherit_target_dependencies() {
+fn inherit_own_features() {
     let p = project()
         .file(
             "Cargo.toml",
             r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [package]
+            name = "foo"
+            version = "1.2.5"
+
+            [features]
+            workspace = true
+            local = []
+
             [workspace]
-            members = ["bar"]
+            members = []
+            [workspace.package.features]
+            default = ["shared"]
+            shared = []
+            "#,
+        )
+        .file(
+            "src/main.rs",
+            r#"
+            #[cfg(not(feature = "shared"))]
+            compile_error!("feature `shared` should be enabled");
+            fn main() {}
+            "#,
+        )
+        .build();
+
+    p.cargo("build --features local")
+        .masquerade_as_nightly_cargo()
+        .run();
+    p.cargo("read-manifest")
+        .masquerade_as_nightly_cargo()
+        .with_stdout_contains(r#"[..]"features":{"default":["shared"],"local":[],"shared":[]}[..]"#)
+        .run();
+}
+
+#[cargo_test]
+fn error_inherited_feature_conflict() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [package]
+            name = "foo"
+            version = "1.2.5"
+
+            [features]
+            workspace = true
+            shared = []
+
+            [workspace]
+            members = []
+            [workspace.package.features]
+            shared = []
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  feature `shared` is defined in `[features]` but is also inherited from the workspace \
+  root manifest's `workspace.package.features`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn inherited_dependencies_union_features() {
+    Package::new("dep", "0.1.0")
+        .feature("fancy", &["fancy_dep"])
+        .feature("dancy", &["dancy_dep"])
+        .add_dep(Dependency::new("fancy_dep", "0.2").optional(true))
+        .add_dep(Dependency::new("dancy_dep", "0.6").optional(true))
+        .file("src/lib.rs", "")
+        .publish();
+
+    Package::new("fancy_dep", "0.2.4").publish();
+    Package::new("dancy_dep", "0.6.8").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep = { workspace = true, features = ["dancy"] }
+
+            [workspace]
+            members = []
             [workspace.dependencies]
-            dep = "0.1"
+            dep = { version = "0.1", features = ["fancy"] }
         "#,
         )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[UPDATING] `[..]` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] fancy_dep v0.2.4 ([..])
+[DOWNLOADED] dep v0.1.0 ([..])
+[DOWNLOADED] dancy_dep v0.6.8 ([..])
+[COMPILING] [..]
+[COMPILING] [..]
+[COMPILING] dep v0.1.0
+[COMPILING] bar v0.2.0 ([CWD])
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains("dep"));
+    assert!(lockfile.contains("fancy_dep"));
+    assert!(lockfile.contains("dancy_dep"));
+}
+
+fn edition_at_least_project(edition: &str) -> cargo_test_support::Project {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.package]
+            edition = "2018"
+            "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            &format!(
+                r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [package]
+            name = "bar"
+            version = "0.1.0"
+            edition = {}
+            "#,
+                edition
+            ),
+        )
+        .file("bar/src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn inherit_edition_at_least() {
+    let p = edition_at_least_project("{ workspace = true }");
+    p.cargo("metadata --format-version 1 --no-deps")
+        .masquerade_as_nightly_cargo()
+        .with_stdout_contains(r#"[..]"edition":"2018"[..]"#)
+        .run();
+
+    let p = edition_at_least_project(r#"{ workspace = true, at-least = "2018" }"#);
+    p.cargo("metadata --format-version 1 --no-deps")
+        .masquerade_as_nightly_cargo()
+        .with_stdout_contains(r#"[..]"edition":"2018"[..]"#)
+        .run();
+
+    let p = edition_at_least_project(r#"{ workspace = true, at-least = "2021" }"#);
+    p.cargo("metadata --format-version 1 --no-deps")
+        .masquerade_as_nightly_cargo()
+        .with_stdout_contains(r#"[..]"edition":"2021"[..]"#)
+        .run();
+}
+
+#[cargo_test]
+fn error_inherit_edition_at_least_older() {
+    let p = edition_at_least_project(r#"{ workspace = true, at-least = "2015" }"#);
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to load manifest for workspace member `[CWD]/bar`
+
+Caused by:
+  failed to parse manifest at `[CWD]/bar/Cargo.toml`
+
+Caused by:
+  `edition.at-least` is `2015`, which is older than the edition `2018` inherited from the workspace
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn error_at_least_on_other_field() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [package]
+            name = "foo"
+            version = { workspace = true, at-least = "1.0.0" }
+
+            [workspace]
+            [workspace.package]
+            version = "1.2.3"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  `at-least` is only supported for `package.edition`, not `package.version`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn inherit_workspace_fields() {
+    registry::init();
+
+    let p = project().build();
+
+    let _ = git::repo(&paths::root().join("foo"))
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.package]
+            version = "1.2.3"
+            authors = ["Rustaceans"]
+            description = "This is a crate"
+            documentation = "https://www.rust-lang.org/learn"
+            readme = "README.md"
+            homepage = "https://www.rust-lang.org"
+            repository = "https://github.com/example/example"
+            license = "MIT"
+            license-file = "LICENSE"
+            keywords = ["cli"]
+            categories = ["development-tools"]
+            publish = true
+            edition = "2018"
+            rust-version = "1.60"
+            exclude = ["foo.txt"]
+            include = ["bar.txt", "**/*.rs", "Cargo.toml", "LICENSE", "README.md"]
+            [workspace.package.badges]
+            gitlab = { repository = "https://gitlab.com/rust-lang/rust", branch = "master" }
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            badges.workspace = true
+            cargo-features = ["workspace-inheritance"]
+            [package]
+            name = "bar"
+            workspace = ".."
+            version.workspace = true
+            authors.workspace = true
+            description.workspace = true
+            documentation.workspace = true
+            readme.workspace = true
+            homepage.workspace = true
+            repository.workspace = true
+            license.workspace = true
+            license-file.workspace = true
+            keywords.workspace = true
+            categories.workspace = true
+            publish.workspace = true
+            edition.workspace = true
+            rust-version.workspace = true
+            exclude.workspace = true
+            include.workspace = true
+        "#,
+        )
+        .file("LICENSE", "license")
+        .file("README.md", "README.md")
+        .file("bar/src/main.rs", "fn main() {}")
+        .file("bar/foo.txt", "") // should be ignored when packaging
+        .file("bar/bar.txt", "") // should be included when packaging
+        .build();
+
+    p.cargo("publish --token sekrit")
+        .masquerade_as_nightly_cargo()
+        .cwd("bar")
+        .run();
+    publish::validate_upload_with_contents(
+        r#"
+        {
+          "authors": ["Rustaceans"],
+          "badges": {
+            "gitlab": { "branch": "master", "repository": "https://gitlab.com/rust-lang/rust" }
+          },
+          "categories": ["development-tools"],
+          "deps": [],
+          "description": "This is a crate",
+          "documentation": "https://www.rust-lang.org/learn",
+          "features": {},
+          "homepage": "https://www.rust-lang.org",
+          "keywords": ["cli"],
+          "license": "MIT",
+          "license_file": "../LICENSE",
+          "links": null,
+          "name": "bar",
+          "readme": "README.md",
+          "readme_file": "../README.md",
+          "repository": "https://github.com/example/example",
+          "vers": "1.2.3"
+          }
+        "#,
+        "bar-1.2.3.crate",
+        &[
+            "Cargo.lock",
+            "Cargo.toml",
+            "Cargo.toml.orig",
+            "src/main.rs",
+            "README.md",
+            "LICENSE",
+            ".cargo_vcs_info.json",
+            "bar.txt",
+        ],
+        &[(
+            "Cargo.toml",
+            &format!(
+                r#"{}
+cargo-features = ["workspace-inheritance"]
+
+[package]
+edition = "2018"
+rust-version = "1.60"
+name = "bar"
+version = "1.2.3"
+authors = ["Rustaceans"]
+exclude = ["foo.txt"]
+include = [
+    "bar.txt",
+    "**/*.rs",
+    "Cargo.toml",
+    "LICENSE",
+    "README.md",
+]
+publish = true
+description = "This is a crate"
+homepage = "https://www.rust-lang.org"
+documentation = "https://www.rust-lang.org/learn"
+readme = "README.md"
+keywords = ["cli"]
+categories = ["development-tools"]
+license = "MIT"
+license-file = "LICENSE"
+repository = "https://github.com/example/example"
+
+[badges.gitlab]
+branch = "master"
+repository = "https://gitlab.com/rust-lang/rust"
+"#,
+                cargo::core::package::MANIFEST_PREAMBLE
+            ),
+        )],
+    );
+}
+
+#[cargo_test]
+fn inherit_dependencies() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.dependencies]
+            dep = "0.1"
+            dep-build = "0.8"
+            dep-dev = "0.5.2"
+        "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            workspace = ".."
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep.workspace = true
+            [build-dependencies]
+            dep-build.workspace = true
+            [dev-dependencies]
+            dep-dev.workspace = true
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    Package::new("dep", "0.1.2").publish();
+    Package::new("dep-build", "0.8.2").publish();
+    Package::new("dep-dev", "0.5.2").publish();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[UPDATING] `[..]` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep-build v0.8.2 ([..])
+[DOWNLOADED] dep v0.1.2 ([..])
+[COMPILING] dep v0.1.2
+[COMPILING] bar v0.2.0 ([CWD]/bar)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+
+    p.cargo("check").masquerade_as_nightly_cargo().run();
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains("dep"));
+    assert!(lockfile.contains("dep-dev"));
+    assert!(lockfile.contains("dep-build"));
+    p.cargo("publish --token sekrit")
+        .masquerade_as_nightly_cargo()
+        .cwd("bar")
+        .run();
+    publish::validate_upload_with_contents(
+        r#"
+        {
+          "authors": [],
+          "badges": {},
+          "categories": [],
+          "deps": [
+            {
+              "default_features": true,
+              "features": [],
+              "kind": "normal",
+              "name": "dep",
+              "optional": false,
+              "registry": "https://github.com/rust-lang/crates.io-index",
+              "target": null,
+              "version_req": "^0.1"
+            },
+            {
+              "default_features": true,
+              "features": [],
+              "kind": "dev",
+              "name": "dep-dev",
+              "optional": false,
+              "registry": "https://github.com/rust-lang/crates.io-index",
+              "target": null,
+              "version_req": "^0.5.2"
+            },
+            {
+              "default_features": true,
+              "features": [],
+              "kind": "build",
+              "name": "dep-build",
+              "optional": false,
+              "registry": "https://github.com/rust-lang/crates.io-index",
+              "target": null,
+              "version_req": "^0.8"
+            }
+          ],
+          "description": null,
+          "documentation": null,
+          "features": {},
+          "homepage": null,
+          "keywords": [],
+          "license": null,
+          "license_file": null,
+          "links": null,
+          "name": "bar",
+          "readme": null,
+          "readme_file": null,
+          "repository": null,
+          "vers": "0.2.0"
+          }
+        "#,
+        "bar-0.2.0.crate",
+        &["Cargo.toml", "Cargo.toml.orig", "Cargo.lock", "src/main.rs"],
+        &[(
+            "Cargo.toml",
+            &format!(
+                r#"{}
+cargo-features = ["workspace-inheritance"]
+
+[package]
+name = "bar"
+version = "0.2.0"
+authors = []
+
+[dependencies.dep]
+version = "0.1"
+
+[dev-dependencies.dep-dev]
+version = "0.5.2"
+
+[build-dependencies.dep-build]
+version = "0.8"
+"#,
+                cargo::core::package::MANIFEST_PREAMBLE
+            ),
+        )],
+    );
+}
+
+#[cargo_test]
+fn inherit_target_dependencies() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.dependencies]
+            dep = "0.1"
+        "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            workspace = ".."
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [target.'cfg(unix)'.dependencies]
+            dep.workspace = true
+            [target.'cfg(windows)'.dependencies]
+            dep.workspace = true
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    Package::new("dep", "0.1.2").publish();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[UPDATING] `[..]` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] dep v0.1.2 ([..])
+[COMPILING] dep v0.1.2
+[COMPILING] bar v0.2.0 ([CWD]/bar)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains("dep"));
+}
+
+#[cargo_test]
+fn inherit_dependency_override_optional() {
+    Package::new("dep", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.dependencies]
+            dep = "0.1.0"
+        "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            workspace = ".."
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep = { workspace = true, optional = true }
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[UPDATING] `[..]` index
+[COMPILING] bar v0.2.0 ([CWD]/bar)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn inherit_dependency_features() {
+    Package::new("dep", "0.1.0")
+        .feature("fancy", &["fancy_dep"])
+        .add_dep(Dependency::new("fancy_dep", "0.2").optional(true))
+        .file("src/lib.rs", "")
+        .publish();
+
+    Package::new("fancy_dep", "0.2.4").publish();
+    Package::new("dancy_dep", "0.6.8").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep = { workspace = true, features = ["fancy"] }
+
+            [workspace]
+            members = []
+            [workspace.dependencies]
+            dep = "0.1"
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[UPDATING] `[..]` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] fancy_dep v0.2.4 ([..])
+[DOWNLOADED] dep v0.1.0 ([..])
+[COMPILING] fancy_dep v0.2.4
+[COMPILING] dep v0.1.0
+[COMPILING] bar v0.2.0 ([CWD])
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains("dep"));
+    assert!(lockfile.contains("fancy_dep"));
+}
+
+#[cargo_test]
+fn inherit_dependency_features_extend_by_default() {
+    Package::new("dep", "0.1.0")
+        .feature("fancy", &["fancy_dep"])
+        .feature("dancy", &["dancy_dep"])
+        .add_dep(Dependency::new("fancy_dep", "0.2").optional(true))
+        .add_dep(Dependency::new("dancy_dep", "0.6").optional(true))
+        .file("src/lib.rs", "")
+        .publish();
+
+    Package::new("fancy_dep", "0.2.4").publish();
+    Package::new("dancy_dep", "0.6.8").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep = { workspace = true, features = ["dancy"] }
+
+            [workspace]
+            members = []
+            [workspace.dependencies]
+            dep = { version = "0.1", features = ["fancy"] }
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("generate-lockfile")
+        .masquerade_as_nightly_cargo()
+        .run();
+
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains("dancy_dep"));
+    assert!(lockfile.contains("fancy_dep"));
+}
+
+#[cargo_test]
+fn inherit_dependency_features_replace() {
+    Package::new("dep", "0.1.0")
+        .feature("fancy", &["fancy_dep"])
+        .feature("dancy", &["dancy_dep"])
+        .add_dep(Dependency::new("fancy_dep", "0.2").optional(true))
+        .add_dep(Dependency::new("dancy_dep", "0.6").optional(true))
+        .file("src/lib.rs", "")
+        .publish();
+
+    Package::new("fancy_dep", "0.2.4").publish();
+    Package::new("dancy_dep", "0.6.8").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep = { workspace = true, features = ["dancy"], inherit-features = false }
+
+            [workspace]
+            members = []
+            [workspace.dependencies]
+            dep = { version = "0.1", features = ["fancy"] }
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("generate-lockfile")
+        .masquerade_as_nightly_cargo()
+        .run();
+
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains("dancy_dep"));
+    assert!(!lockfile.contains("fancy_dep"));
+}
+
+#[cargo_test]
+fn error_inherit_features_without_workspace() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [project]
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep = { version = "0.1", inherit-features = false }
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr_contains(
+            "  `inherit-features` may only be used with `workspace = true` for key `dependencies.dep`",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn error_workspace_dependency_with_source_keys() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            name = "bar"
+            version = "0.2.0"
+            [dependencies]
+            dep = { workspace = true, version = "2" }
+            [workspace.dependencies]
+            dep = "0.1"
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    let error = |keys: &str, pronoun: &str| {
+        format!(
+            "  {} cannot be used with `workspace = true`, set {} in \
+             `[workspace.dependencies]` instead for key `dependencies.dep`",
+            keys, pronoun
+        )
+    };
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr_contains(error("`version`", "it"))
+        .run();
+
+    for (spec, keys) in [
+        (r#"path = "dep""#, "`path`"),
+        (r#"git = "https://example.com/dep""#, "`git`"),
+        (r#"registry = "alternative""#, "`registry`"),
+        (
+            r#"version = "2", git = "https://example.com/dep", rev = "abc1234""#,
+            "`version`, `git`, `rev`",
+        ),
+    ] {
+        p.change_file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                cargo-features = ["workspace-inheritance"]
+
+                [project]
+                name = "bar"
+                version = "0.2.0"
+                [dependencies]
+                dep = {{ workspace = true, {} }}
+                [workspace.dependencies]
+                dep = "0.1"
+                "#,
+                spec
+            ),
+        );
+        let pronoun = if keys.contains(',') { "them" } else { "it" };
+        p.cargo("build")
+            .masquerade_as_nightly_cargo()
+            .with_status(101)
+            .with_stderr_contains(error(keys, pronoun))
+            .run();
+    }
+}
+
+#[cargo_test]
+fn inherit_detailed_dependencies() {
+    let git_project = git::new("detailed", |project| {
+        project
+            .file("Cargo.toml", &basic_lib_manifest("detailed"))
+            .file(
+                "src/detailed.rs",
+                r#"
+                pub fn hello() -> &'static str {
+                    "hello world"
+                }
+            "#,
+            )
+    });
+
+    // Make a new branch based on the current HEAD commit
+    let repo = git2::Repository::open(&git_project.root()).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    let head = repo.find_commit(head).unwrap();
+    repo.branch("branchy", &head, true).unwrap();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.dependencies]
+            detailed = {{ git = '{}', branch = "branchy" }}
+        "#,
+                git_project.url()
+            ),
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            workspace = ".."
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            detailed.workspace = true
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    let git_root = git_project.root();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(&format!(
+            "\
+[UPDATING] git repository `{}`\n\
+[COMPILING] detailed v0.5.0 ({}?branch=branchy#[..])\n\
+[COMPILING] bar v0.2.0 ([CWD]/bar)\n\
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]\n",
+            path2url(&git_root),
+            path2url(&git_root),
+        ))
+        .run();
+}
+
+#[cargo_test]
+fn inherit_path_dependencies() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.dependencies]
+            dep = { path = "dep" }
+        "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            workspace = ".."
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep.workspace = true
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .file("dep/Cargo.toml", &basic_manifest("dep", "0.9.0"))
+        .file("dep/src/lib.rs", "")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[COMPILING] dep v0.9.0 ([CWD]/dep)
+[COMPILING] bar v0.2.0 ([CWD]/bar)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+
+    let lockfile = p.read_lockfile();
+    assert!(lockfile.contains("dep"));
+}
+
+#[cargo_test]
+fn inherit_path_dependencies_nested_member() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["crates/nested/bar"]
+            [workspace.dependencies]
+            local = { path = "crates/local" }
+        "#,
+        )
+        .file(
+            "crates/nested/bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            workspace = "../../.."
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            local.workspace = true
+        "#,
+        )
+        .file("crates/nested/bar/src/main.rs", "fn main() {}")
+        .file("crates/local/Cargo.toml", &basic_manifest("local", "0.9.0"))
+        .file("crates/local/src/lib.rs", "")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[COMPILING] local v0.9.0 ([CWD]/crates/local)
+[COMPILING] bar v0.2.0 ([CWD]/crates/nested/bar)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn error_inherit_path_dependency_escaping_workspace() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.dependencies]
+            dep = { path = "../dep" }
+        "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            workspace = ".."
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep.workspace = true
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to load manifest for workspace member `[CWD]/bar`
+
+Caused by:
+  failed to parse manifest at `[CWD]/bar/Cargo.toml`
+
+Caused by:
+  `dep` has a path `../dep` that escapes the workspace root `[CWD]`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn error_inherit_absolute_path_dependency_escaping_workspace() {
+    let dep = paths::root().join("dep");
+    std::fs::create_dir_all(&dep).unwrap();
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                [workspace]
+                members = ["bar"]
+                [workspace.dependencies]
+                dep = {{ path = '{}' }}
+            "#,
+                dep.display()
+            ),
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [project]
+            workspace = ".."
+            name = "bar"
+            version = "0.2.0"
+            authors = []
+            [dependencies]
+            dep.workspace = true
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to load manifest for workspace member `[CWD]/bar`
+
+Caused by:
+  failed to parse manifest at `[CWD]/bar/Cargo.toml`
+
+Caused by:
+  `dep` has a path `[ROOT]/dep` that escapes the workspace root `[CWD]`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn error_workspace_false() {
+    registry::init();
+
+    let p = project().build();
+
+    let _ = git::repo(&paths::root().join("foo"))
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            [package]
+            name = "bar"
+            workspace = ".."
+            version = "1.2.3"
+            authors = ["rustaceans"]
+            description = { workspace = false }
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .cwd("bar")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  `workspace=false` is unsupported for `package.description`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn error_workspace_dependency_looked_for_workspace_itself() {
+    registry::init();
+
+    let p = project().build();
+
+    let _ = git::repo(&paths::root().join("foo"))
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+            [package]
+            name = "bar"
+            version = "1.2.3"
+            workspace = ".."
+
+            [dependencies]
+            dep.workspace = true
+
+            [workspace]
+            members = ["bar"]
+
+            [workspace.dependencies]
+            dep.workspace = true
+
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  dep was specified as `workspace.dependencies.dep.workspace = true`, but \
+  workspace dependencies cannot specify `workspace = true`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn error_malformed_workspace_root() {
+    registry::init();
+
+    let p = project().build();
+
+    let _ = git::repo(&paths::root().join("foo"))
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = [invalid toml
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [package]
+            name = "bar"
+            workspace = ".."
+            version = "1.2.3"
+            authors = ["rustaceans"]
+        "#,
+        )
+        .file("bar/src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .masquerade_as_nightly_cargo()
+        .cwd("bar")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]/foo/Cargo.toml`
+
+Caused by:
+  [..]
+
+Caused by:
+  [..]
+    |
+  3 |             members = [invalid toml
+    |                        ^
+  Unexpected `i`
+  Expected newline or `#`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn error_no_root_workspace() {
+    registry::init();
+
+    let p = project().build();
+
+    let _ = git::repo(&paths::root().join("foo"))
         .file(
             "bar/Cargo.toml",
             r#"
             cargo-features = ["workspace-inheritance"]
 
-            [project]
-            workspace = ".."
+            [package]
             name = "bar"
-            version = "0.2.0"
-            authors = []
-            [target.'cfg(unix)'.dependencies]
-            dep.workspace = true
-            [target.'cfg(windows)'.dependencies]
-            dep.workspace = true
+            workspace = ".."
+            version = "1.2.3"
+            authors = ["rustaceans"]
+            description.workspace = true
         "#,
         )
+        .file("src/main.rs", "fn main() {}")
         .file("bar/src/main.rs", "fn main() {}")
         .build();
 
-    Package::new("dep", "0.1.2").publish();
-
     p.cargo("build")
         .masquerade_as_nightly_cargo()
+        .cwd("bar")
+        .with_status(101)
         .with_stderr(
             "\
-[UPDATING] `[..]` index
-[DOWNLOADING] crates ...
-[DOWNLOADED] dep v0.1.2 ([..])
-[COMPILING] dep v0.1.2
-[COMPILING] bar v0.2.0 ([CWD]/bar)
-[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+[ERROR] failed to parse manifest at `[..]/Cargo.toml`
+
+Caused by:
+  error inheriting `description` from workspace root manifest's `workspace.package.description`
+
+Caused by:
+  root of a workspace inferred but wasn't a root: [..]/Cargo.toml
 ",
         )
         .run();
-
-    let lockfile = p.read_lockfile();
-    assert!(lockfile.contains("dep"));
 }
 
 #[cargo_test]
-fn inherit_dependency_override_optional() {
-    Package::new("dep", "0.1.0").publish();
+fn error_inherit_unspecified_dependency() {
+    let p = project().build();
 
-    let p = project()
+    let _ = git::repo(&paths::root().join("foo"))
         .file(
             "Cargo.toml",
             r#"
             [workspace]
             members = ["bar"]
-            [workspace.dependencies]
-            dep = "0.1.0"
-        "#,
+            "#,
         )
+        .file("src/main.rs", "fn main() {}")
         .file(
             "bar/Cargo.toml",
             r#"
             cargo-features = ["workspace-inheritance"]
 
-            [project]
-            workspace = ".."
+            [package]
             name = "bar"
-            version = "0.2.0"
-            authors = []
+            workspace = ".."
+            version = "1.2.3"
+            authors = ["rustaceans"]
             [dependencies]
-            dep = { workspace = true, optional = true }
+            foo.workspace = true
         "#,
         )
         .file("bar/src/main.rs", "fn main() {}")
@@ -962,208 +2124,193 @@ fn inherit_dependency_override_optional() {
 
     p.cargo("build")
         .masquerade_as_nightly_cargo()
+        .cwd("bar")
+        .with_status(101)
         .with_stderr(
             "\
-[UPDATING] `[..]` index
-[COMPILING] bar v0.2.0 ([CWD]/bar)
-[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  error reading `dependencies.foo` from workspace root manifest's `workspace.dependencies.foo`
+
+Caused by:
+  `workspace.dependencies` was not defined
 ",
         )
         .run();
 }
 
 #[cargo_test]
-fn inherit_dependency_features() {
-    Package::new("dep", "0.1.0")
-        .feature("fancy", &["fancy_dep"])
-        .add_dep(Dependency::new("fancy_dep", "0.2").optional(true))
-        .file("src/lib.rs", "")
-        .publish();
+fn workspace_inheritance_not_enabled() {
+    registry::init();
 
-    Package::new("fancy_dep", "0.2.4").publish();
-    Package::new("dancy_dep", "0.6.8").publish();
+    let p = project().build();
 
-    let p = project()
+    let _ = git::repo(&paths::root().join("foo"))
         .file(
             "Cargo.toml",
             r#"
-            cargo-features = ["workspace-inheritance"]
-
-            [project]
-            name = "bar"
-            version = "0.2.0"
-            authors = []
-            [dependencies]
-            dep = { workspace = true, features = ["fancy"] }
+            [package]
+            name = "foo"
+            version = "1.2.5"
+            authors = ["rustaceans"]
+            description.workspace = true
 
             [workspace]
             members = []
-            [workspace.dependencies]
-            dep = "0.1"
-        "#,
+            "#,
         )
         .file("src/main.rs", "fn main() {}")
         .build();
 
     p.cargo("build")
         .masquerade_as_nightly_cargo()
+        .with_status(101)
         .with_stderr(
             "\
-[UPDATING] `[..]` index
-[DOWNLOADING] crates ...
-[DOWNLOADED] fancy_dep v0.2.4 ([..])
-[DOWNLOADED] dep v0.1.0 ([..])
-[COMPILING] fancy_dep v0.2.4
-[COMPILING] dep v0.1.0
-[COMPILING] bar v0.2.0 ([CWD])
-[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  feature `workspace-inheritance` is required
+
+  The package requires the Cargo feature called `workspace-inheritance`, \
+  but that feature is not stabilized in this version of Cargo (1.[..]).
+  Consider adding `cargo-features = [\"workspace-inheritance\"]` to the top of Cargo.toml \
+  (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#workspace-inheritance \
+  for more information about the status of this feature.
 ",
         )
         .run();
-
-    let lockfile = p.read_lockfile();
-    assert!(lockfile.contains("dep"));
-    assert!(lockfile.contains("fancy_dep"));
 }
 
 #[cargo_test]
-fn inherit_detailed_dependencies() {
-    let git_project = git::new("detailed", |project| {
-        project
-            .file("Cargo.toml", &basic_lib_manifest("detailed"))
-            .file(
-                "src/detailed.rs",
-                r#"
-                pub fn hello() -> &'static str {
-                    "hello world"
-                }
+fn nightly_required() {
+    registry::init();
+
+    let p = project().build();
+
+    let _ = git::repo(&paths::root().join("foo"))
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [package]
+            name = "foo"
+            version = "1.2.5"
+            authors = ["rustaceans"]
+            description.workspace = true
+
+            [workspace]
+            members = []
             "#,
-            )
-    });
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
 
-    // Make a new branch based on the current HEAD commit
-    let repo = git2::Repository::open(&git_project.root()).unwrap();
-    let head = repo.head().unwrap().target().unwrap();
-    let head = repo.find_commit(head).unwrap();
-    repo.branch("branchy", &head, true).unwrap();
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  the cargo feature `workspace-inheritance` requires a nightly version of Cargo, \
+  but this is the `stable` channel
+  See [..]
+  See https://doc.rust-lang.org/[..]cargo/reference/unstable.html#workspace-inheritance \
+  for more information about using this feature.
+",
+        )
+        .run();
+}
 
+#[cargo_test]
+fn implicit_rust_version_inheritance() {
     let p = project()
         .file(
             "Cargo.toml",
-            &format!(
-                r#"
+            r#"
             [workspace]
             members = ["bar"]
-            [workspace.dependencies]
-            detailed = {{ git = '{}', branch = "branchy" }}
-        "#,
-                git_project.url()
-            ),
+            [workspace.package]
+            rust-version = "1.60"
+            "#,
         )
         .file(
             "bar/Cargo.toml",
             r#"
-            cargo-features = ["workspace-inheritance"]
+            cargo-features = ["implicit-rust-version-inheritance"]
 
-            [project]
-            workspace = ".."
+            [package]
             name = "bar"
-            version = "0.2.0"
-            authors = []
-            [dependencies]
-            detailed.workspace = true
-        "#,
+            version = "0.1.0"
+            edition = "2021"
+            "#,
         )
         .file("bar/src/main.rs", "fn main() {}")
         .build();
 
-    let git_root = git_project.root();
-
-    p.cargo("build")
+    p.cargo("read-manifest")
         .masquerade_as_nightly_cargo()
-        .with_stderr(&format!(
-            "\
-[UPDATING] git repository `{}`\n\
-[COMPILING] detailed v0.5.0 ({}?branch=branchy#[..])\n\
-[COMPILING] bar v0.2.0 ([CWD]/bar)\n\
-[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]\n",
-            path2url(&git_root),
-            path2url(&git_root),
-        ))
+        .cwd("bar")
+        .with_stdout_contains(r#"[..]"rust_version":"1.60"[..]"#)
         .run();
 }
 
 #[cargo_test]
-fn inherit_path_dependencies() {
+fn implicit_rust_version_inheritance_gate_off() {
     let p = project()
         .file(
             "Cargo.toml",
             r#"
             [workspace]
             members = ["bar"]
-            [workspace.dependencies]
-            dep = { path = "dep" }
-        "#,
+            [workspace.package]
+            rust-version = "1.60"
+            "#,
         )
         .file(
             "bar/Cargo.toml",
             r#"
-            cargo-features = ["workspace-inheritance"]
-
-            [project]
-            workspace = ".."
+            [package]
             name = "bar"
-            version = "0.2.0"
-            authors = []
-            [dependencies]
-            dep.workspace = true
-        "#,
+            version = "0.1.0"
+            edition = "2021"
+            "#,
         )
         .file("bar/src/main.rs", "fn main() {}")
-        .file("dep/Cargo.toml", &basic_manifest("dep", "0.9.0"))
-        .file("dep/src/lib.rs", "")
         .build();
 
-    p.cargo("build")
-        .masquerade_as_nightly_cargo()
-        .with_stderr(
-            "\
-[COMPILING] dep v0.9.0 ([CWD]/dep)
-[COMPILING] bar v0.2.0 ([CWD]/bar)
-[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
-",
-        )
+    p.cargo("read-manifest")
+        .cwd("bar")
+        .with_stdout_contains(r#"[..]"rust_version":null[..]"#)
         .run();
-
-    let lockfile = p.read_lockfile();
-    assert!(lockfile.contains("dep"));
 }
 
 #[cargo_test]
-fn error_workspace_false() {
-    registry::init();
-
-    let p = project().build();
-
-    let _ = git::repo(&paths::root().join("foo"))
+fn implicit_rust_version_inheritance_older_than_edition() {
+    let p = project()
         .file(
             "Cargo.toml",
             r#"
             [workspace]
             members = ["bar"]
+            [workspace.package]
+            rust-version = "1.50"
             "#,
         )
-        .file("src/main.rs", "fn main() {}")
         .file(
             "bar/Cargo.toml",
             r#"
+            cargo-features = ["implicit-rust-version-inheritance"]
+
             [package]
             name = "bar"
-            workspace = ".."
-            version = "1.2.3"
-            authors = ["rustaceans"]
-            description = { workspace = false }
-        "#,
+            version = "0.1.0"
+            edition = "2021"
+            "#,
         )
         .file("bar/src/main.rs", "fn main() {}")
         .build();
@@ -1177,131 +2324,103 @@ fn error_workspace_false() {
 [ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
 
 Caused by:
-  `workspace=false` is unsupported for `package.description`
+  rust-version 1.50 is older than first version (1.56.0) required by the specified edition (2021)
 ",
         )
         .run();
 }
 
 #[cargo_test]
-fn error_workspace_dependency_looked_for_workspace_itself() {
-    registry::init();
-
-    let p = project().build();
-
-    let _ = git::repo(&paths::root().join("foo"))
+fn implicit_version_inheritance() {
+    let p = project()
         .file(
             "Cargo.toml",
             r#"
-            cargo-features = ["workspace-inheritance"]
-            [package]
-            name = "bar"
-            version = "1.2.3"
-            workspace = ".."
-
-            [dependencies]
-            dep.workspace = true
-
             [workspace]
             members = ["bar"]
+            [workspace.package]
+            version = "1.2.3"
+            "#,
+        )
+        .file(
+            "bar/Cargo.toml",
+            r#"
+            cargo-features = ["implicit-version-inheritance"]
 
-            [workspace.dependencies]
-            dep.workspace = true
-
+            [package]
+            name = "bar"
+            edition = "2021"
             "#,
         )
-        .file("src/main.rs", "fn main() {}")
+        .file("bar/src/main.rs", "fn main() {}")
         .build();
 
-    p.cargo("build")
+    p.cargo("read-manifest")
         .masquerade_as_nightly_cargo()
-        .with_status(101)
-        .with_stderr(
-            "\
-[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
-
-Caused by:
-  dep was specified as `workspace.dependencies.dep.workspace = true`, but \
-  workspace dependencies cannot specify `workspace = true`
-",
-        )
+        .cwd("bar")
+        .with_stdout_contains(r#"[..]"version":"1.2.3"[..]"#)
         .run();
 }
 
 #[cargo_test]
-fn error_malformed_workspace_root() {
-    registry::init();
-
-    let p = project().build();
-
-    let _ = git::repo(&paths::root().join("foo"))
+fn implicit_version_inheritance_gate_off() {
+    let p = project()
         .file(
             "Cargo.toml",
             r#"
             [workspace]
-            members = [invalid toml
+            members = ["bar"]
+            [workspace.package]
+            version = "1.2.3"
             "#,
         )
-        .file("src/main.rs", "fn main() {}")
         .file(
             "bar/Cargo.toml",
             r#"
-            cargo-features = ["workspace-inheritance"]
-
             [package]
             name = "bar"
-            workspace = ".."
-            version = "1.2.3"
-            authors = ["rustaceans"]
-        "#,
+            edition = "2021"
+            "#,
         )
         .file("bar/src/main.rs", "fn main() {}")
         .build();
 
     p.cargo("build")
-        .masquerade_as_nightly_cargo()
         .cwd("bar")
         .with_status(101)
         .with_stderr(
             "\
-[ERROR] failed to parse manifest at `[..]/foo/Cargo.toml`
-
-Caused by:
-  [..]
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
 
 Caused by:
-  [..]
-    |
-  3 |             members = [invalid toml
-    |                        ^
-  Unexpected `i`
-  Expected newline or `#`
+  `package.version` is required
 ",
         )
         .run();
 }
 
 #[cargo_test]
-fn error_no_root_workspace() {
-    registry::init();
-
-    let p = project().build();
-
-    let _ = git::repo(&paths::root().join("foo"))
+fn implicit_version_inheritance_missing_everywhere() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["bar"]
+            [workspace.package]
+            edition = "2021"
+            "#,
+        )
         .file(
             "bar/Cargo.toml",
             r#"
-            cargo-features = ["workspace-inheritance"]
+            cargo-features = ["implicit-version-inheritance"]
 
             [package]
             name = "bar"
-            workspace = ".."
-            version = "1.2.3"
-            authors = ["rustaceans"]
-            description.workspace = true
-        "#,
+            edition = "2021"
+            "#,
         )
-        .file("src/main.rs", "fn main() {}")
         .file("bar/src/main.rs", "fn main() {}")
         .build();
 
@@ -1311,31 +2430,32 @@ fn error_no_root_workspace() {
         .with_status(101)
         .with_stderr(
             "\
-[ERROR] failed to parse manifest at `[..]/Cargo.toml`
-
-Caused by:
-  error inheriting `description` from workspace root manifest's `workspace.package.description`
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
 
 Caused by:
-  root of a workspace inferred but wasn't a root: [..]/Cargo.toml
+  `package.version` is required, and there is no `workspace.package.version` to inherit it from
 ",
         )
         .run();
 }
 
 #[cargo_test]
-fn error_inherit_unspecified_dependency() {
-    let p = project().build();
+fn metadata_to_toml_has_inherited_values() {
+    use super::read_manifest::with_workspace;
 
-    let _ = git::repo(&paths::root().join("foo"))
+    // The keys are deliberately out of the documented order.
+    let p = project()
         .file(
             "Cargo.toml",
             r#"
             [workspace]
             members = ["bar"]
+            [workspace.package]
+            keywords = ["cli"]
+            license = "MIT OR Apache-2.0"
+            description = "inherited description"
             "#,
         )
-        .file("src/main.rs", "fn main() {}")
         .file(
             "bar/Cargo.toml",
             r#"
@@ -1343,115 +2463,178 @@ fn error_inherit_unspecified_dependency() {
 
             [package]
             name = "bar"
-            workspace = ".."
-            version = "1.2.3"
-            authors = ["rustaceans"]
-            [dependencies]
-            foo.workspace = true
-        "#,
+            version = "0.1.0"
+            links = "bar"
+            categories = ["command-line-utilities"]
+            keywords.workspace = true
+            license-file = "LICENSE"
+            license.workspace = true
+            repository = "https://example.com/bar"
+            homepage = "https://example.com"
+            readme = "README.md"
+            documentation = "https://docs.example.com/bar"
+            description.workspace = true
+            authors = ["Alice"]
+            "#,
         )
-        .file("bar/src/main.rs", "fn main() {}")
+        .file("bar/src/lib.rs", "")
+        .file("bar/build.rs", "fn main() {}")
+        .file("bar/README.md", "")
+        .file("bar/LICENSE", "")
         .build();
 
-    p.cargo("build")
-        .masquerade_as_nightly_cargo()
-        .cwd("bar")
-        .with_status(101)
-        .with_stderr(
-            "\
-[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
-
-Caused by:
-  error reading `dependencies.foo` from workspace root manifest's `workspace.dependencies.foo`
-
-Caused by:
-  `workspace.dependencies` was not defined
-",
-        )
-        .run();
+    let metadata = with_workspace(p.root().join("bar/Cargo.toml"), |ws| {
+        ws.current().unwrap().manifest().metadata().to_toml()
+    });
+    assert_eq!(
+        metadata.to_string(),
+        "\
+authors = [\"Alice\"]
+description = \"inherited description\"
+documentation = \"https://docs.example.com/bar\"
+readme = \"README.md\"
+homepage = \"https://example.com\"
+repository = \"https://example.com/bar\"
+license = \"MIT OR Apache-2.0\"
+license-file = \"LICENSE\"
+keywords = [\"cli\"]
+categories = [\"command-line-utilities\"]
+links = \"bar\"
+"
+    );
 }
 
 #[cargo_test]
-fn workspace_inheritance_not_enabled() {
-    registry::init();
-
-    let p = project().build();
-
-    let _ = git::repo(&paths::root().join("foo"))
+fn inherit_license_file_nested_member() {
+    let p = project()
         .file(
             "Cargo.toml",
             r#"
-            [package]
-            name = "foo"
-            version = "1.2.5"
-            authors = ["rustaceans"]
-            description.workspace = true
-
             [workspace]
-            members = []
+            members = ["crates/bar"]
+            [workspace.package]
+            license-file = "LICENSE"
             "#,
         )
-        .file("src/main.rs", "fn main() {}")
+        .file(
+            "crates/bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
+
+            [package]
+            name = "bar"
+            version = "0.1.0"
+            description = "bar"
+            license-file.workspace = true
+            "#,
+        )
+        .file("crates/bar/src/lib.rs", "")
+        .file("LICENSE", "license")
         .build();
 
-    p.cargo("build")
+    p.cargo("read-manifest")
         .masquerade_as_nightly_cargo()
-        .with_status(101)
-        .with_stderr(
-            "\
-[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
-
-Caused by:
-  feature `workspace-inheritance` is required
+        .cwd("crates/bar")
+        .with_stdout_contains(r#"[..]"license_file":"../../LICENSE"[..]"#)
+        .run();
 
-  The package requires the Cargo feature called `workspace-inheritance`, \
-  but that feature is not stabilized in this version of Cargo (1.[..]).
-  Consider adding `cargo-features = [\"workspace-inheritance\"]` to the top of Cargo.toml \
-  (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
-  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#workspace-inheritance \
-  for more information about the status of this feature.
-",
-        )
+    p.cargo("package --no-verify")
+        .masquerade_as_nightly_cargo()
+        .cwd("crates/bar")
         .run();
+    let f = std::fs::File::open(p.root().join("target/package/bar-0.1.0.crate")).unwrap();
+    publish::validate_crate_contents(
+        f,
+        "bar-0.1.0.crate",
+        &["Cargo.toml", "Cargo.toml.orig", "src/lib.rs", "LICENSE"],
+        &[("LICENSE", "license")],
+    );
 }
 
 #[cargo_test]
-fn nightly_required() {
-    registry::init();
+fn inherit_license_file_above_workspace_root() {
+    let p = project()
+        .file(
+            "ws/Cargo.toml",
+            r#"
+            [workspace]
+            members = ["crates/bar"]
+            [workspace.package]
+            license-file = "../LICENSE"
+            "#,
+        )
+        .file(
+            "ws/crates/bar/Cargo.toml",
+            r#"
+            cargo-features = ["workspace-inheritance"]
 
-    let p = project().build();
+            [package]
+            name = "bar"
+            version = "0.1.0"
+            license-file.workspace = true
+            "#,
+        )
+        .file("ws/crates/bar/src/lib.rs", "")
+        .file("LICENSE", "license")
+        .build();
 
-    let _ = git::repo(&paths::root().join("foo"))
+    p.cargo("read-manifest")
+        .masquerade_as_nightly_cargo()
+        .cwd("ws/crates/bar")
+        .with_stdout_contains(r#"[..]"license_file":"../../../LICENSE"[..]"#)
+        .run();
+}
+
+#[cargo_test]
+fn inherit_license_file_missing() {
+    let p = project()
         .file(
             "Cargo.toml",
             r#"
+            [workspace]
+            members = ["crates/bar"]
+            [workspace.package]
+            license-file = "LICENSE"
+            "#,
+        )
+        .file(
+            "crates/bar/Cargo.toml",
+            r#"
             cargo-features = ["workspace-inheritance"]
 
             [package]
-            name = "foo"
-            version = "1.2.5"
-            authors = ["rustaceans"]
-            description.workspace = true
-
-            [workspace]
-            members = []
+            name = "bar"
+            version = "0.1.0"
+            license-file.workspace = true
             "#,
         )
-        .file("src/main.rs", "fn main() {}")
+        .file("crates/bar/src/lib.rs", "")
         .build();
 
-    p.cargo("build")
-        .with_status(101)
+    // Like a `license-file` set in the package itself, a missing file
+    // doesn't get in the way of building, only of packaging.
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .cwd("crates/bar")
         .with_stderr(
             "\
-[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+[CHECKING] bar v0.1.0 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
 
-Caused by:
-  the cargo feature `workspace-inheritance` requires a nightly version of Cargo, \
-  but this is the `stable` channel
-  See [..]
-  See https://doc.rust-lang.org/[..]cargo/reference/unstable.html#workspace-inheritance \
-  for more information about using this feature.
+    p.cargo("package --no-verify")
+        .masquerade_as_nightly_cargo()
+        .cwd("crates/bar")
+        .with_stderr(
+            "\
+[WARNING] manifest has no description, documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[WARNING] license-file `../../LICENSE` does not appear to exist (relative to `[CWD]`).
+Please update the license-file setting in the manifest at `[CWD]/Cargo.toml`
+This may become a hard error in the future.
+[PACKAGING] bar v0.1.0 ([CWD])
 ",
         )
         .run();
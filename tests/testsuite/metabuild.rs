@@ -1,5 +1,6 @@
 //! Tests for the metabuild feature (declarative build scripts).
 
+use super::read_manifest::with_workspace;
 use cargo_test_support::{
     basic_lib_manifest, basic_manifest, is_coarse_mtime, project, registry::Package, rustc_host,
     Project,
@@ -80,6 +81,38 @@ fn basic_project() -> Project {
         .build()
 }
 
+#[cargo_test]
+fn metabuild_required_features_used() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["metabuild", "test-dummy-unstable"]
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                metabuild = ["mb"]
+
+                [build-dependencies]
+                mb = {path="mb"}
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("mb/Cargo.toml", &basic_lib_manifest("mb"))
+        .file("mb/src/lib.rs", "pub fn metabuild() {}")
+        .build();
+
+    let used = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        ws.current()
+            .unwrap()
+            .manifest()
+            .required_features_used()
+            .clone()
+    });
+    // `test-dummy-unstable` is declared but nothing in the manifest needs it.
+    assert_eq!(used.iter().collect::<Vec<_>>(), ["metabuild"]);
+}
+
 #[cargo_test]
 fn metabuild_basic() {
     let p = basic_project();
@@ -1,5 +1,6 @@
 //! Tests for the `cargo package` command.
 
+use super::read_manifest::with_workspace;
 use cargo_test_support::paths::CargoPathExt;
 use cargo_test_support::publish::validate_crate_contents;
 use cargo_test_support::registry::{self, Package};
@@ -546,7 +547,6 @@ fn include() {
             "\
 [WARNING] manifest has no description[..]
 See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
-[WARNING] both package.include and package.exclude are specified; the exclude list will be ignored
 [PACKAGING] foo v0.0.1 ([..])
 [ARCHIVING] .cargo_vcs_info.json
 [ARCHIVING] .dotfile
@@ -560,6 +560,60 @@ See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for
         .run();
 }
 
+#[cargo_test]
+fn include_and_exclude_warns_on_build() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [project]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                exclude = ["*.txt"]
+                include = ["**/*.rs", "Cargo.toml"]
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .with_stderr(
+            "\
+[WARNING] both package.include and package.exclude are specified; the exclude list will be ignored
+[COMPILING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn only_exclude_does_not_warn() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [project]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                exclude = ["*.txt"]
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("build")
+        .with_stderr(
+            "\
+[COMPILING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn package_lib_with_bin() {
     let p = project()
@@ -1148,6 +1202,74 @@ version = "1.0"
     );
 }
 
+#[cargo_test]
+fn generated_manifest_platform_git_and_path_deps() {
+    // Git and path dependencies under `[target]` tables are turned into
+    // registry dependencies that keep their version and platform key.
+    registry::init();
+    let git_dep = git::new("gitdep", |p| {
+        p.file("Cargo.toml", &basic_manifest("gitdep", "0.2.0"))
+            .file("src/lib.rs", "")
+    });
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [project]
+                    name = "foo"
+                    version = "0.0.1"
+                    license = "MIT"
+                    description = "foo"
+
+                    [target.'cfg(unix)'.dependencies]
+                    gitdep = {{ git = '{0}', version = "0.2", branch = "master" }}
+
+                    [target.'cfg(target_os = "windows")'.build-dependencies]
+                    bar = {{ path = "bar", version = "0.1" }}
+
+                    [target.'cfg(unix)'.dev-dependencies]
+                    devonly = {{ path = "devonly" }}
+                "#,
+                git_dep.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", "")
+        .file("devonly/Cargo.toml", &basic_manifest("devonly", "0.1.0"))
+        .file("devonly/src/lib.rs", "")
+        .build();
+
+    p.cargo("package --no-verify").run();
+
+    let f = File::open(&p.root().join("target/package/foo-0.0.1.crate")).unwrap();
+    let rewritten_toml = format!(
+        r#"{}
+[package]
+name = "foo"
+version = "0.0.1"
+description = "foo"
+license = "MIT"
+
+[target."cfg(target_os = \"windows\")".build-dependencies.bar]
+version = "0.1"
+
+[target."cfg(unix)".dependencies.gitdep]
+version = "0.2"
+"#,
+        cargo::core::package::MANIFEST_PREAMBLE,
+    );
+
+    validate_crate_contents(
+        f,
+        "foo-0.0.1.crate",
+        &["Cargo.toml", "Cargo.toml.orig", "src/lib.rs"],
+        &[("Cargo.toml", &rewritten_toml)],
+    );
+}
+
 #[cargo_test]
 fn ignore_workspace_specifier() {
     let p = project()
@@ -1252,7 +1374,248 @@ fn test_edition() {
 }
 
 #[cargo_test]
-fn edition_with_metadata() {
+fn edition_with_metadata() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                edition = "2018"
+
+                [package.metadata.docs.rs]
+                features = ["foobar"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("package").run();
+}
+
+#[cargo_test]
+fn invalid_keywords() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                description = "foo"
+                license = "MIT"
+                homepage = "foo"
+                keywords = ["cli", "no std"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("package --list")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  invalid keyword `no std`, keywords must start with a letter and contain only \
+letters, numbers, `_`, and `-`
+",
+        )
+        .run();
+
+    // `--no-metadata` doesn't skip the check.
+    p.cargo("package --list --no-metadata")
+        .with_status(101)
+        .with_stderr_contains("  invalid keyword `no std`, [..]")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            keywords = ["cli", "3d"]
+        "#,
+    );
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("  invalid keyword `3d`, [..]")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            keywords = ["abcdefghijklmnopqrstu"]
+        "#,
+    );
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("  keyword `abcdefghijklmnopqrstu` is longer than 20 characters")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            keywords = ["a", "b", "c", "d", "e", "f"]
+        "#,
+    );
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("  package has 6 keywords, but at most 5 are allowed")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            keywords = ["cli", "no_std", "gamedev-tools", "x86", "abcdefghijklmnopqrst"]
+        "#,
+    );
+    p.cargo("check").run();
+}
+
+#[cargo_test]
+fn invalid_categories() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                description = "foo"
+                license = "MIT"
+                homepage = "foo"
+                categories = ["development-tools::cargo-plugins", "Parsing"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("package --list")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  invalid category `Parsing`, categories are lowercase slugs separated by `::`, \
+such as `development-tools::cargo-plugins`
+",
+        )
+        .run();
+
+    // `--no-metadata` doesn't skip the check.
+    p.cargo("package --list --no-metadata")
+        .with_status(101)
+        .with_stderr_contains("  invalid category `Parsing`, [..]")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            categories = ["a::"]
+        "#,
+    );
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("  invalid category `a::`, [..]")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            categories = ["a", "b", "c", "d", "e", "f"]
+        "#,
+    );
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr_contains("  package has 6 categories, but at most 5 are allowed")
+        .run();
+
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            categories = ["development-tools::cargo-plugins", "no-std"]
+        "#,
+    );
+    p.cargo("check").run();
+}
+
+#[cargo_test]
+fn include_invalid_glob() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                include = ["src/**", "[z-a]"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_stderr(
+            "\
+[WARNING] invalid pattern `[z-a]` in `package.include`: error parsing glob '[z-a]': invalid range; 'z' > 'a'
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn exclude_valid_glob() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                exclude = ["*.txt", "[ab]/**", "!keep.txt"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_stderr(
+            "\
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn path_dep_in_excluded_dir() {
     let p = project()
         .file(
             "Cargo.toml",
@@ -1260,17 +1623,49 @@ fn edition_with_metadata() {
                 [package]
                 name = "foo"
                 version = "0.0.1"
-                authors = []
-                edition = "2018"
+                exclude = ["vendor"]
 
-                [package.metadata.docs.rs]
-                features = ["foobar"]
+                [dependencies]
+                bar = { path = "vendor/bar", version = "0.1" }
+                baz = { path = "libs/baz", version = "0.1" }
             "#,
         )
         .file("src/lib.rs", "")
+        .file("vendor/bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("vendor/bar/src/lib.rs", "")
+        .file("libs/baz/Cargo.toml", &basic_manifest("baz", "0.1.0"))
+        .file("libs/baz/src/lib.rs", "")
         .build();
 
-    p.cargo("package").run();
+    // Only packaging cares about what gets excluded.
+    p.cargo("check")
+        .with_stderr(
+            "\
+[CHECKING] [..]
+[CHECKING] [..]
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    p.cargo("package --list")
+        .with_stdout(
+            "\
+Cargo.toml
+Cargo.toml.orig
+src/lib.rs
+",
+        )
+        .with_stderr(
+            "\
+[WARNING] manifest has no description, license, license-file, documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[WARNING] path dependency `vendor/bar` is inside a directory excluded by `package.exclude`, \
+so it is not included when packaging
+",
+        )
+        .run();
 }
 
 #[cargo_test]
@@ -1296,10 +1691,7 @@ fn test_edition_malformed() {
 error: failed to parse manifest at `[..]`
 
 Caused by:
-  failed to parse the `edition` key
-
-Caused by:
-  supported edition values are `2015`, `2018`, or `2021`, but `chicken` is unknown
+  invalid `edition` value `chicken`, expected one of `2015`, `2018`, `2021`
 "
             .to_string(),
         )
@@ -1328,16 +1720,41 @@ fn test_edition_from_the_future() {
 error: failed to parse manifest at `[..]`
 
 Caused by:
-  failed to parse the `edition` key
-
-Caused by:
-  this version of Cargo is older than the `2038` edition, and only supports `2015`, `2018`, and `2021` editions.
+  invalid `edition` value `2038`, this version of Cargo is older than that edition and only supports `2015`, `2018`, `2021`
 "
             .to_string(),
         )
         .run();
 }
 
+#[cargo_test]
+fn test_edition_unknown_year() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                edition = "2023"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+error: failed to parse manifest at `[..]`
+
+Caused by:
+  invalid `edition` value `2023`, [..]only supports `2015`, `2018`, `2021`
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn do_not_package_if_src_was_modified() {
     let p = project()
@@ -1916,6 +2333,212 @@ src/lib.rs
     assert!(orig.contains("license-file = \"../LICENSE\""));
 }
 
+#[cargo_test]
+fn license_file_single_entry_list() {
+    // A list with a single license-file is published in the string form.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["multiple-license-files"]
+
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            license-file = ["LICENSE"]
+            description = "foo"
+            homepage = "https://example.com/"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("LICENSE", "license text")
+        .build();
+
+    p.cargo("package")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[PACKAGING] foo v1.0.0 [..]
+[VERIFYING] foo v1.0.0 [..]
+[COMPILING] foo v1.0.0 [..]
+[FINISHED] [..]
+",
+        )
+        .run();
+    let f = File::open(&p.root().join("target/package/foo-1.0.0.crate")).unwrap();
+    validate_crate_contents(
+        f,
+        "foo-1.0.0.crate",
+        &["Cargo.toml", "Cargo.toml.orig", "LICENSE", "src/lib.rs"],
+        &[("LICENSE", "license text")],
+    );
+    let manifest = read_to_string(p.root().join("target/package/foo-1.0.0/Cargo.toml")).unwrap();
+    assert!(manifest.contains("license-file = \"LICENSE\""));
+}
+
+#[cargo_test]
+fn multiple_license_files() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["multiple-license-files"]
+
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            license-file = ["LICENSE-MIT", "../LICENSE-APACHE"]
+            description = "foo"
+            homepage = "https://example.com/"
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+                #[cfg(not(windows))]
+                // The second path is rewritten in the packaged manifest.
+                const _: () = assert!(matches!(
+                    env!("CARGO_PKG_LICENSE_FILE").as_bytes(),
+                    b"LICENSE-MIT:../LICENSE-APACHE" | b"LICENSE-MIT:LICENSE-APACHE"
+                ));
+            "#,
+        )
+        .file("LICENSE-MIT", "mit license")
+        .file("../LICENSE-APACHE", "apache license")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[CHECKING] foo v1.0.0 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    // Files outside of the package are copied into the root.
+    p.cargo("package --list")
+        .masquerade_as_nightly_cargo()
+        .with_stdout(
+            "\
+Cargo.toml
+Cargo.toml.orig
+LICENSE-APACHE
+LICENSE-MIT
+src/lib.rs
+",
+        )
+        .with_stderr("")
+        .run();
+
+    p.cargo("package")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[PACKAGING] foo v1.0.0 ([CWD])
+[VERIFYING] foo v1.0.0 ([CWD])
+[COMPILING] foo v1.0.0 ([CWD]/target/package/foo-1.0.0)
+[FINISHED] [..]
+",
+        )
+        .run();
+    let f = File::open(&p.root().join("target/package/foo-1.0.0.crate")).unwrap();
+    validate_crate_contents(
+        f,
+        "foo-1.0.0.crate",
+        &[
+            "Cargo.toml",
+            "Cargo.toml.orig",
+            "LICENSE-APACHE",
+            "LICENSE-MIT",
+            "src/lib.rs",
+        ],
+        &[
+            ("LICENSE-APACHE", "apache license"),
+            ("LICENSE-MIT", "mit license"),
+        ],
+    );
+    let manifest =
+        std::fs::read_to_string(p.root().join("target/package/foo-1.0.0/Cargo.toml")).unwrap();
+    assert!(manifest.contains(
+        r#"license-file = [
+    "LICENSE-MIT",
+    "LICENSE-APACHE",
+]"#
+    ));
+}
+
+#[cargo_test]
+fn multiple_license_files_same_name() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["multiple-license-files"]
+
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            license-file = ["../a/LICENSE", "../b/LICENSE"]
+            description = "foo"
+            homepage = "https://example.com/"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("../a/LICENSE", "a")
+        .file("../b/LICENSE", "b")
+        .build();
+
+    p.cargo("package --list")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] license-file `../a/LICENSE` and `../b/LICENSE` are both outside of the package \
+and would both be copied to `LICENSE` in its root
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn multiple_license_files_requires_cargo_feature() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "1.0.0"
+            license-file = ["LICENSE"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("LICENSE", "license text")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  feature `multiple-license-files` is required
+
+  The package requires the Cargo feature called `multiple-license-files`, \
+but that feature is not stabilized in this version of Cargo (1.[..]).
+  Consider adding `cargo-features = [\"multiple-license-files\"]` to the top of Cargo.toml \
+(above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#multiple-license-files \
+for more information about the status of this feature.
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 #[cfg(not(windows))] // Don't want to create invalid files on Windows.
 fn package_restricted_windows() {
@@ -2325,3 +2948,110 @@ See [..]
     assert!(p.root().join("target/package/foo-0.0.1.crate").is_file());
     assert!(p.root().join("target/package/bar-0.0.1.crate").is_file());
 }
+
+#[cargo_test]
+fn published_toml_string() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["foo"]
+
+                [workspace.package]
+                description = "foo"
+            "#,
+        )
+        .file(
+            "foo/Cargo.toml",
+            r#"
+                cargo-features = ["workspace-inheritance"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                description.workspace = true
+                license = "MIT"
+                readme = "../README.md"
+
+                [dependencies]
+                bar = { path = "../bar", version = "0.0.1" }
+            "#,
+        )
+        .file("foo/src/lib.rs", "")
+        .file("README.md", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    let published = with_workspace(p.root().join("foo/Cargo.toml"), |ws| {
+        let pkg = ws.current().unwrap();
+        pkg.manifest()
+            .original()
+            .to_published_toml_string(ws, pkg.root())
+            .unwrap()
+    });
+
+    let reparsed: toml_edit::easy::Value = toml_edit::easy::from_str(&published).unwrap();
+    let package = &reparsed["package"];
+    assert_eq!(package["description"].as_str(), Some("foo"));
+    assert_eq!(package["readme"].as_str(), Some("README.md"));
+    assert!(package.get("workspace").is_none());
+    let bar = &reparsed["dependencies"]["bar"];
+    assert_eq!(bar["version"].as_str(), Some("0.0.1"));
+    assert!(bar.get("path").is_none());
+}
+
+#[cargo_test]
+fn published_toml_uses_kebab_case_keys() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                description = "foo"
+                license = "MIT"
+
+                [lib]
+                crate_type = ["rlib"]
+
+                [dependencies]
+                bar = { path = "bar", version = "0.0.1", default_features = false }
+
+                [dev_dependencies]
+                baz = { path = "baz", version = "0.0.1" }
+
+                [target.'cfg(unix)'.build_dependencies]
+                bar = { path = "bar", version = "0.0.1", default_features = false }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.0.1"))
+        .file("bar/src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.0.1"))
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    let published = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        let pkg = ws.current().unwrap();
+        pkg.manifest()
+            .original()
+            .to_published_toml_string(ws, pkg.root())
+            .unwrap()
+    });
+
+    assert!(!published.contains('_'), "{}", published);
+    let reparsed: toml_edit::easy::Value = toml_edit::easy::from_str(&published).unwrap();
+    assert_eq!(
+        reparsed["dependencies"]["bar"]["default-features"].as_bool(),
+        Some(false)
+    );
+    assert_eq!(
+        reparsed["target"]["cfg(unix)"]["build-dependencies"]["bar"]["default-features"].as_bool(),
+        Some(false)
+    );
+    assert!(reparsed["dev-dependencies"].get("baz").is_some());
+    assert_eq!(reparsed["lib"]["crate-type"][0].as_str(), Some("rlib"));
+}
@@ -1333,10 +1333,43 @@ fn non_crates_io() {
 error: failed to parse manifest at `[..]`
 
 Caused by:
-  [patch] entry `some-other-source` should be a URL or registry name
+  registry `some-other-source` is not configured; add it under [registries.some-other-source]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn invalid_url_key() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+
+                [patch.'https://']
+                bar = { path = 'bar' }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("bar/src/lib.rs", r#""#)
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+error: failed to parse manifest at `[..]`
+
+Caused by:
+  [patch] entry `https://` should be a URL or registry name
 
 Caused by:
-  invalid url `some-other-source`: relative URL without a base
+  invalid url `https://`: empty host
 ",
         )
         .run();
@@ -2542,3 +2575,42 @@ foo v0.1.0 [..]
         ))
         .run();
 }
+
+#[cargo_test]
+fn patched_names_across_sources() {
+    use super::read_manifest::with_workspace;
+    use cargo::sources::CRATES_IO_INDEX;
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [patch.crates-io]
+                bar = { path = "bar" }
+                renamed = { path = "baz", package = "baz" }
+
+                [patch."https://example.com/qux"]
+                qux = { path = "qux" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let names = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        ws.current().unwrap().manifest().patched_names()
+    });
+    let crates_io = CRATES_IO_INDEX.parse::<url::Url>().unwrap();
+    let other = "https://example.com/qux".parse::<url::Url>().unwrap();
+    assert_eq!(
+        names,
+        vec![
+            (other, "qux".to_string()),
+            (crates_io.clone(), "bar".to_string()),
+            (crates_io, "baz".to_string()),
+        ]
+    );
+}
@@ -1,5 +1,7 @@
 //! Tests for `path` dependencies.
 
+use super::config::ConfigBuilder;
+use cargo::util::toml::collect_path_deps_acyclic;
 use cargo_test_support::paths::{self, CargoPathExt};
 use cargo_test_support::registry::Package;
 use cargo_test_support::{basic_lib_manifest, basic_manifest, main_file, project};
@@ -1137,3 +1139,80 @@ fn catch_tricky_cycle() {
         .with_status(101)
         .run();
 }
+
+#[cargo_test]
+fn collect_path_deps() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.5.0"
+
+                [dependencies]
+                bar = { path = "bar" }
+                baz = { path = "baz" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.5.0"
+
+                [dependencies]
+                baz = { path = "../baz" }
+            "#,
+        )
+        .file("bar/src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "0.5.0"))
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    let config = ConfigBuilder::new().build();
+    let deps = collect_path_deps_acyclic(&p.root(), &config).unwrap();
+    assert_eq!(deps, [p.root().join("bar"), p.root().join("baz")]);
+}
+
+#[cargo_test]
+fn collect_path_deps_cycle() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.5.0"
+
+                [dependencies]
+                bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file(
+            "bar/Cargo.toml",
+            r#"
+                [package]
+                name = "bar"
+                version = "0.5.0"
+
+                [dependencies]
+                foo = { path = ".." }
+            "#,
+        )
+        .file("bar/src/lib.rs", "")
+        .build();
+
+    let config = ConfigBuilder::new().build();
+    let err = collect_path_deps_acyclic(&p.root(), &config).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "cyclic path dependencies found: {root} -> {root}/bar -> {root}",
+            root = p.root().display()
+        )
+    );
+}
@@ -181,8 +181,11 @@ fn invalid_inherits() {
     p.cargo("build")
         .with_status(101)
         .with_stderr(
-            "error: profile `release-lto` inherits from `.release`, \
-             but that profile is not defined",
+            "\
+[ERROR] profile `release-lto` inherits from `.release`, but that profile is not defined
+
+<tab>Did you mean `release`?
+",
         )
         .run();
 }
@@ -216,6 +219,151 @@ fn non_existent_inherits() {
         .run();
 }
 
+#[cargo_test]
+fn misspelled_inherits() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+
+                [profile.release-lto]
+                inherits = "release"
+                lto = true
+
+                [profile.release-lto-debug]
+                inherits = "release-ltoo"
+                debug = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build --profile release-lto-debug")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] profile `release-lto-debug` inherits from `release-ltoo`, but that profile is not defined
+
+<tab>Did you mean `release-lto`?
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn lint_release_overflow_checks() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["lint-release-overflow-checks"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+
+                [profile.release-lto]
+                inherits = "release"
+                lto = true
+
+                [profile.release-lto-checked]
+                inherits = "release-lto"
+                overflow-checks = true
+
+                [profile.dev-checked]
+                inherits = "dev"
+                overflow-checks = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[WARNING] profile `release-lto-checked` inherits from `release` but sets \
+`overflow-checks = true`, which `release` turns off. Remove it unless the checks \
+are wanted in this profile.
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn lint_release_overflow_checks_already_on() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["lint-release-overflow-checks"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+
+                [profile.release]
+                overflow-checks = true
+
+                [profile.release-lto]
+                inherits = "release"
+                lto = true
+
+                [profile.release-lto-checked]
+                inherits = "release-lto"
+                overflow-checks = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn inherits_custom_chain() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+
+                [profile.release-lto]
+                inherits = "release"
+                lto = true
+
+                [profile.release-lto-debug]
+                inherits = "release-lto"
+                debug = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build --profile release-lto-debug -v")
+        .with_stderr_contains("[..]-C opt-level=3 [..]-C debuginfo=2 [..]")
+        .run();
+}
+
 #[cargo_test]
 fn self_inherits() {
     let p = project()
@@ -100,6 +100,10 @@ fn profile_override_bad_settings() {
             "`rpath` may not be specified in a `package` profile",
         ),
         ("package = {}", "package-specific profiles cannot be nested"),
+        (
+            "panic = \"abort\"\nlto = true",
+            "`panic` and `lto` may not be specified in a `package` profile",
+        ),
     ];
     for &(snippet, expected) in bad_values.iter() {
         let p = project()
@@ -132,6 +136,38 @@ fn profile_override_bad_settings() {
     }
 }
 
+#[cargo_test]
+fn build_override_bad_settings_reported_together() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [profile.dev.build-override]
+                panic = "abort"
+                lto = true
+                rpath = true
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  `panic`, `lto` and `rpath` may not be specified in a `build-override` profile
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn profile_override_hierarchy() {
     // Test that the precedence rules are correct for different types.
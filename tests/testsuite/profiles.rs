@@ -110,6 +110,39 @@ fn debug_override_1() {
         .run();
 }
 
+#[cargo_test]
+fn debug_named_levels() {
+    for (name, level) in [
+        ("none", "0"),
+        ("line-directives-only", "1"),
+        ("line-tables-only", "1"),
+        ("limited", "1"),
+        ("full", "2"),
+    ] {
+        let p = project()
+            .file(
+                "Cargo.toml",
+                &format!(
+                    r#"
+                        [package]
+                        name = "test"
+                        version = "0.0.0"
+                        authors = []
+
+                        [profile.dev]
+                        debug = "{}"
+                    "#,
+                    name
+                ),
+            )
+            .file("src/lib.rs", "")
+            .build();
+        p.cargo("build -v")
+            .with_stderr_contains(&format!("[RUNNING] `rustc [..]-C debuginfo={} [..]", level))
+            .run();
+    }
+}
+
 fn check_opt_level_override(profile_level: &str, rustc_level: &str) {
     let p = project()
         .file(
@@ -162,6 +195,116 @@ fn opt_level_overrides() {
     }
 }
 
+#[cargo_test]
+fn opt_level_quoted_number() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "test"
+                version = "0.0.0"
+
+                [profile.dev]
+                opt-level = "2"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    p.cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  must be `0`, `1`, `2`, `3`, `s` or `z`, but found the string: \"2\"; \
+numeric levels are written without quotes, as in `opt-level = 2` for key `profile.dev.opt-level`
+",
+        )
+        .run();
+
+    // Quoted `s` and `z` are the only string forms and are still accepted.
+    check_opt_level_override("\"s\"", "s");
+}
+
+#[cargo_test]
+fn split_debuginfo_values() {
+    let manifest = |value: &str| {
+        format!(
+            r#"
+                [package]
+                name = "test"
+                version = "0.0.0"
+
+                [profile.dev]
+                split-debuginfo = "{}"
+            "#,
+            value
+        )
+    };
+    let p = project()
+        .file("Cargo.toml", &manifest("off"))
+        .file("src/lib.rs", "")
+        .build();
+
+    for value in ["off", "packed", "unpacked"] {
+        p.change_file("Cargo.toml", &manifest(value));
+        p.cargo("metadata --no-deps --format-version 1").run();
+    }
+
+    p.change_file("Cargo.toml", &manifest("pack"));
+    p.cargo("metadata --no-deps --format-version 1")
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  `split-debuginfo` setting of `pack` is not a valid setting, must be `off`, `packed`, or `unpacked`
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn codegen_units_one_with_incremental() {
+    let manifest = |incremental: bool| {
+        format!(
+            r#"
+                [package]
+                name = "test"
+                version = "0.0.0"
+
+                [profile.dist]
+                inherits = "release"
+                codegen-units = 1
+                incremental = {}
+            "#,
+            incremental
+        )
+    };
+    let p = project()
+        .file("Cargo.toml", &manifest(true))
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_stderr(
+            "\
+[WARNING] profile `dist` sets both `codegen-units = 1` and `incremental = true`; \
+incremental compilation splits crates into more codegen units, \
+so `codegen-units = 1` will not give single-unit codegen
+[CHECKING] test v0.0.0 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    p.change_file("Cargo.toml", &manifest(false));
+    p.cargo("check").with_stderr("[FINISHED] [..]").run();
+}
+
 #[cargo_test]
 fn top_level_overrides_deps() {
     let p = project()
@@ -671,6 +814,67 @@ fn rustflags_works_with_env() {
         .run();
 }
 
+#[cargo_test]
+fn rustflags_mis_split() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["profile-rustflags"]
+
+            [profile.dev]
+            rustflags = ["-C", "link-dead-code", "yes", "--cfg foo"]
+
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr_contains(
+            "\
+[WARNING] `profile.dev.rustflags` passes `link-dead-code` and `yes` as separate arguments \
+to `-C`, which rustc does not accept; did you mean `-C link-dead-code=yes`?
+[WARNING] `profile.dev.rustflags` entry `--cfg foo` looks like more than one flag; \
+each entry is passed to rustc as a single argument, so give every flag its own entry
+",
+        )
+        .run();
+
+    // Correctly split flags, including values that contain spaces and
+    // `link-args`, whose value is a list of arguments.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["profile-rustflags"]
+
+            [profile.dev]
+            rustflags = [
+                "-C", "link-dead-code=yes", "-Clink-arg=-B nope", "--cfg", "foo",
+                "-Clink-args=-Wl,--foo -Wl,--bar",
+            ]
+
+            [package]
+            name = "foo"
+            version = "0.0.1"
+        "#,
+    );
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[CHECKING] foo [..]
+[FINISHED] [..]
+",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn rustflags_requires_cargo_feature() {
     let p = project()
@@ -317,6 +317,156 @@ The registry `crates-io` is not listed in the `publish` value in Cargo.toml.
         .run();
 }
 
+#[cargo_test]
+fn unpublishable_crate_with_reason() {
+    let registry = registry::init();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["publish-reason"]
+
+                [project]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                license = "MIT"
+                description = "foo"
+                publish = { registry = false, reason = "internal only" }
+            "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build();
+
+    p.cargo("publish --index")
+        .arg(registry.index_url().as_str())
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] `foo` cannot be published.
+The registry `crates-io` is not listed in the `publish` value in Cargo.toml.
+Reason: internal only
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn publish_reason_requires_cargo_feature() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [project]
+                name = "foo"
+                version = "0.0.1"
+                publish = { registry = false, reason = "internal only" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  feature `publish-reason` is required
+
+  The package requires the Cargo feature called `publish-reason`, \
+  but that feature is not stabilized in this version of Cargo (1.[..]).
+  Consider adding `cargo-features = [\"publish-reason\"]` to the top of Cargo.toml \
+  (above the [package] table) to tell Cargo you are opting in to use this unstable feature.
+  See https://doc.rust-lang.org/nightly/cargo/reference/unstable.html#publish-reason \
+  for more information about the status of this feature.
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn publish_reason_with_registry_true() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["publish-reason"]
+
+                [project]
+                name = "foo"
+                version = "0.0.1"
+                publish = { registry = true, reason = "internal only" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[CWD]/Cargo.toml`
+
+Caused by:
+  `package.publish.reason` is only allowed when `registry = false`, \
+  as the package can be published
+",
+        )
+        .run();
+
+    // Without a reason, `registry = true` is the same as `publish = true`.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            cargo-features = ["publish-reason"]
+
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            publish = { registry = true }
+        "#,
+    );
+    p.cargo("read-manifest")
+        .masquerade_as_nightly_cargo()
+        .with_json(
+            r#"
+{
+  "name": "foo",
+  "version": "0.0.1",
+  "publish": null,
+  "id": "[..]",
+  "license": null,
+  "license_file": null,
+  "description": null,
+  "source": null,
+  "dependencies": [],
+  "targets": "{...}",
+  "features": {},
+  "manifest_path": "[..]",
+  "metadata": null,
+  "authors": [],
+  "categories": [],
+  "default_run": null,
+  "keywords": [],
+  "readme": null,
+  "repository": null,
+  "rust_version": null,
+  "homepage": null,
+  "documentation": null,
+  "edition": "2015",
+  "links": null
+}
+"#,
+        )
+        .run();
+}
+
 #[cargo_test]
 fn dont_publish_dirty() {
     registry::init();
@@ -1284,8 +1434,6 @@ homepage = "foo"
 documentation = "foo"
 license = "MIT"
 repository = "foo"
-
-[dev-dependencies]
 "#,
                 cargo::core::package::MANIFEST_PREAMBLE
             ),
@@ -1,6 +1,21 @@
 //! Tests for the `cargo read-manifest` command.
 
-use cargo_test_support::{basic_bin_manifest, main_file, project};
+use super::config::ConfigBuilder;
+use cargo::core::Workspace;
+use cargo_test_support::{basic_bin_manifest, basic_manifest, main_file, project};
+use std::path::Path;
+
+/// Loads the workspace of the manifest at `manifest_path` and hands it to
+/// `f`, for tests that check what Cargo made of a manifest rather than the
+/// output of a command. Nightly features are allowed.
+pub fn with_workspace<R>(
+    manifest_path: impl AsRef<Path>,
+    f: impl FnOnce(&Workspace<'_>) -> R,
+) -> R {
+    let config = ConfigBuilder::new().nightly_features_allowed(true).build();
+    let ws = Workspace::new(manifest_path.as_ref(), &config).unwrap();
+    f(&ws)
+}
 
 fn manifest_output(readme_value: &str) -> String {
     format!(
@@ -175,6 +190,175 @@ fn cargo_read_manifest_default_readme() {
     }
 }
 
+#[cargo_test]
+fn default_readme_search_order() {
+    use cargo::util::toml::{default_readme_files, find_default_readme};
+
+    assert_eq!(
+        default_readme_files(),
+        ["README.md", "README.txt", "README"]
+    );
+
+    let p = project().file("src/lib.rs", "").build();
+    assert_eq!(find_default_readme(&p.root()), None);
+
+    // Each earlier name wins over the ones after it.
+    for readme in default_readme_files().iter().rev() {
+        p.change_file(readme, "Sample project");
+        assert_eq!(find_default_readme(&p.root()).as_deref(), Some(*readme));
+    }
+
+    // A directory with a README name is not a README.
+    let p = project()
+        .file("src/lib.rs", "")
+        .file("README.md/nested", "")
+        .file("README", "")
+        .build();
+    assert_eq!(find_default_readme(&p.root()).as_deref(), Some("README"));
+}
+
+#[cargo_test]
+fn manifest_dependency_sources() {
+    use cargo::core::SourceId;
+    use cargo_test_support::{git, registry};
+
+    registry::init();
+    let git_dep = git::new("gitdep", |p| {
+        p.file("Cargo.toml", &basic_manifest("gitdep", "0.1.0"))
+            .file("src/lib.rs", "")
+    });
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "foo"
+                    version = "0.1.0"
+
+                    [dependencies]
+                    reg1 = "1.0"
+                    gitdep = {{ git = '{}' }}
+                    local = {{ path = "local" }}
+
+                    [dev-dependencies]
+                    reg2 = "1.0"
+                "#,
+                git_dep.url()
+            ),
+        )
+        .file("src/lib.rs", "")
+        .file("local/Cargo.toml", &basic_manifest("local", "0.1.0"))
+        .file("local/src/lib.rs", "")
+        .build();
+
+    let (sources, crates_io) = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        (
+            ws.current().unwrap().manifest().dependency_sources(),
+            SourceId::crates_io(ws.config()).unwrap(),
+        )
+    });
+    assert_eq!(sources.len(), 3);
+    assert!(sources.contains(&crates_io));
+    assert!(sources.iter().any(|s| s.is_git()));
+    assert!(sources.iter().any(|s| s.is_path()));
+}
+
+#[cargo_test]
+fn to_real_manifest_in_memory_uses_predicate() {
+    use cargo::core::SourceId;
+    use cargo::util::toml::TomlManifest;
+    use std::rc::Rc;
+
+    // `build.rs` and `README.md` exist on disk, but the predicate only
+    // reports `README.txt`, so neither on-disk file should be picked up.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [lib]
+                path = "src/lib.rs"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("build.rs", "fn main() {}")
+        .file("README.md", "")
+        .build();
+
+    let config = ConfigBuilder::new().build();
+    let source_id = SourceId::for_path(&p.root()).unwrap();
+    let contents = p.read_file("Cargo.toml");
+    let toml: TomlManifest = toml_edit::easy::from_str(&contents).unwrap();
+    let toml = Rc::new(toml);
+    let root = p.root();
+
+    let is_file = |path: &Path| path == root.join("README.txt");
+    let (manifest, _) =
+        TomlManifest::to_real_manifest_in_memory(&toml, source_id, &root, &config, &is_file)
+            .unwrap();
+    assert_eq!(manifest.metadata().readme.as_deref(), Some("README.txt"));
+    assert!(!manifest.targets().iter().any(|t| t.is_custom_build()));
+
+    // The on-disk variant sees the real files.
+    let (manifest, _) = TomlManifest::to_real_manifest(&toml, source_id, &root, &config).unwrap();
+    assert_eq!(manifest.metadata().readme.as_deref(), Some("README.md"));
+    assert!(manifest.targets().iter().any(|t| t.is_custom_build()));
+}
+
+#[cargo_test]
+fn documentation_or_default() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["documented", "undocumented"]
+            "#,
+        )
+        .file(
+            "documented/Cargo.toml",
+            r#"
+                [package]
+                name = "documented"
+                version = "0.1.0"
+                documentation = "https://example.com/documented"
+            "#,
+        )
+        .file("documented/src/lib.rs", "")
+        .file(
+            "undocumented/Cargo.toml",
+            &basic_manifest("undocumented", "0.1.0"),
+        )
+        .file("undocumented/src/lib.rs", "")
+        .build();
+
+    let docs: Vec<_> = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        ws.members()
+            .map(|pkg| {
+                let metadata = pkg.manifest().metadata();
+                (
+                    metadata.documentation.clone(),
+                    metadata.documentation_or_default(&pkg.name()),
+                )
+            })
+            .collect()
+    });
+    assert_eq!(
+        docs,
+        [
+            (
+                Some("https://example.com/documented".to_string()),
+                Some("https://example.com/documented".to_string())
+            ),
+            (None, Some("https://docs.rs/undocumented".to_string())),
+        ]
+    );
+}
+
 #[cargo_test]
 fn cargo_read_manifest_suppress_default_readme() {
     let p = project()
@@ -204,3 +388,198 @@ fn cargo_read_manifest_defaults_readme_if_true() {
         .with_json(&manifest_output(r#""README.md""#))
         .run();
 }
+
+#[cargo_test]
+fn cargo_read_manifest_with_byte_order_mark() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &format!("\u{feff}{}", basic_bin_manifest("foo")),
+        )
+        .file("src/foo.rs", &main_file(r#""i am foo""#, &[]))
+        .build();
+
+    p.cargo("read-manifest")
+        .with_json(&manifest_output_no_readme())
+        .run();
+}
+
+#[cargo_test]
+fn cargo_read_manifest_with_crlf_line_endings() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            &basic_bin_manifest("foo").replace('\n', "\r\n"),
+        )
+        .file("src/foo.rs", &main_file(r#""i am foo""#, &[]))
+        .build();
+
+    p.cargo("read-manifest")
+        .with_json(&manifest_output_no_readme())
+        .run();
+}
+
+#[cargo_test]
+fn package_and_workspace_metadata() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [package.metadata.docs.rs]
+                all-features = true
+
+                [workspace.metadata]
+                tool = "bar"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let (package, workspace) = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        let toml = ws.current().unwrap().manifest().original();
+        (
+            toml.package_metadata().cloned(),
+            toml.workspace_metadata().cloned(),
+        )
+    });
+    let docs_rs = &package.unwrap()["docs"]["rs"];
+    assert_eq!(docs_rs["all-features"].as_bool(), Some(true));
+    assert_eq!(workspace.unwrap()["tool"].as_str(), Some("bar"));
+}
+
+#[cargo_test]
+fn dependencies_for_kind() {
+    use cargo::core::dependency::DepKind;
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                normal = "1.0"
+
+                [dev_dependencies]
+                dev-alias = "1.0"
+
+                [build_dependencies]
+                build-alias = "1.0"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+    let names = |kind: Option<DepKind>| -> Vec<String> {
+        with_workspace(p.root().join("Cargo.toml"), |ws| {
+            let toml = ws.current().unwrap().manifest().original();
+            toml.dependencies_for_kind(kind)
+                .into_keys()
+                .map(String::from)
+                .collect()
+        })
+    };
+    assert_eq!(names(None), ["normal"]);
+    assert_eq!(names(Some(DepKind::Normal)), ["normal"]);
+    assert_eq!(names(Some(DepKind::Development)), ["dev-alias"]);
+    assert_eq!(names(Some(DepKind::Build)), ["build-alias"]);
+
+    // The `kebab-case` tables win over the aliases.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.1.0"
+
+            [dev-dependencies]
+            dev = "1.0"
+
+            [dev_dependencies]
+            dev-alias = "1.0"
+
+            [build-dependencies]
+            build = "1.0"
+
+            [build_dependencies]
+            build-alias = "1.0"
+        "#,
+    );
+    assert!(names(None).is_empty());
+    assert_eq!(names(Some(DepKind::Development)), ["dev"]);
+    assert_eq!(names(Some(DepKind::Build)), ["build"]);
+}
+
+#[cargo_test]
+fn git_dependencies() {
+    use cargo::core::GitReference;
+    use cargo::util::toml::GitDepInfo;
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                branch = { git = "https://example.com/branch", branch = "dev" }
+                registry = "1.0"
+
+                [dev-dependencies]
+                tag = { git = "https://example.com/tag", tag = "v1.0.0" }
+
+                [target.'cfg(unix)'.build-dependencies]
+                rev = { git = "https://example.com/rev", rev = "abc123" }
+
+                [patch.crates-io]
+                default = { git = "https://example.com/default" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let git_deps = with_workspace(p.root().join("Cargo.toml"), |ws| {
+        ws.current()
+            .unwrap()
+            .manifest()
+            .original()
+            .git_dependencies()
+    });
+    let git_dep = |name: &str, url: &str, reference| GitDepInfo {
+        name: name.to_string(),
+        url: url.to_string(),
+        reference,
+    };
+    assert_eq!(
+        git_deps,
+        [
+            git_dep(
+                "branch",
+                "https://example.com/branch",
+                GitReference::Branch("dev".to_string())
+            ),
+            git_dep(
+                "tag",
+                "https://example.com/tag",
+                GitReference::Tag("v1.0.0".to_string())
+            ),
+            git_dep(
+                "rev",
+                "https://example.com/rev",
+                GitReference::Rev("abc123".to_string())
+            ),
+            git_dep(
+                "default",
+                "https://example.com/default",
+                GitReference::DefaultBranch
+            ),
+        ]
+    );
+}
@@ -2655,3 +2655,70 @@ fn http_requires_trailing_slash() {
         .with_stderr("[ERROR] registry url must end in a slash `/`: sparse+https://index.crates.io")
         .run()
 }
+
+#[cargo_test]
+fn lint_exact_version_pins() {
+    registry::init();
+    Package::new("bar", "1.2.3").publish();
+    Package::new("baz", "1.2.3").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                cargo-features = ["lint-exact-version-pins"]
+
+                [package]
+                name = "foo"
+                version = "0.0.1"
+
+                [dependencies]
+                bar = "=1.2.3"
+                baz = "1.2"
+
+                [dev-dependencies]
+                bar = "=1.2.3"
+
+                [patch.crates-io]
+                baz = { path = "baz" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("baz/Cargo.toml", &basic_manifest("baz", "1.2.4"))
+        .file("baz/src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[WARNING] dependency (bar) is pinned to the exact version `=1.2.3`, \
+which fails to resolve if that version is yanked. \
+Consider `\"1.2.3\"` unless the pin is intentional.
+[UPDATING] `dummy-registry` index
+[DOWNLOADING] crates ...
+[DOWNLOADED] bar v1.2.3 (registry `dummy-registry`)
+[CHECKING] [..]
+[CHECKING] [..]
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    // Without the feature exact pins are left alone.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+
+            [dependencies]
+            bar = "=1.2.3"
+        "#,
+    );
+    p.cargo("check")
+        .with_stderr_does_not_contain("[WARNING][..]")
+        .run();
+}
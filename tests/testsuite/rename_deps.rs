@@ -235,6 +235,37 @@ fn rename_affects_fingerprint() {
         .run();
 }
 
+#[cargo_test]
+fn warn_redundant_rename() {
+    Package::new("foo", "0.1.0").publish();
+    Package::new("bar", "0.1.0").publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "test"
+                version = "0.1.0"
+                authors = []
+
+                [dependencies]
+                foo = { version = "0.1", package = "foo" }
+                baz = { version = "0.1", package = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("build")
+        .with_stderr_contains(
+            "[WARNING] dependency `foo` sets `package = \"foo\"`, which is the same as \
+             its name; the `package` key can be removed",
+        )
+        .with_stderr_does_not_contain("[..]dependency `baz`[..]")
+        .run();
+}
+
 #[cargo_test]
 fn can_run_doc_tests() {
     Package::new("bar", "0.1.0").publish();
@@ -1,6 +1,8 @@
 //! Tests for targets with `rust-version`.
 
-use cargo_test_support::{project, registry::Package};
+use super::read_manifest::with_workspace;
+use cargo_test_support::{basic_manifest, project, registry::Package, rustc_release};
+use semver::Version;
 
 #[cargo_test]
 fn rust_version_satisfied() {
@@ -76,6 +78,60 @@ fn rust_version_bad_pre_release() {
         .run();
 }
 
+#[cargo_test]
+fn rust_version_pre_release_identifier() {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            rust-version = "1.70.0-beta.1"
+            [[bin]]
+            name = "foo"
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build()
+        .cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "error: failed to parse manifest at `[..]`\n\n\
+             Caused by:\n  `rust-version` of \"1.70.0-beta.1\" is not allowed to contain \
+             pre-release or build metadata identifiers, use a value like \"1.70\"",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn rust_version_build_metadata() {
+    project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            rust-version = "1.70.0+foo"
+            [[bin]]
+            name = "foo"
+        "#,
+        )
+        .file("src/main.rs", "fn main() {}")
+        .build()
+        .cargo("build")
+        .with_status(101)
+        .with_stderr(
+            "error: failed to parse manifest at `[..]`\n\n\
+             Caused by:\n  `rust-version` of \"1.70.0+foo\" is not allowed to contain \
+             pre-release or build metadata identifiers, use a value like \"1.70\"",
+        )
+        .run();
+}
+
 #[cargo_test]
 fn rust_version_bad_nonsense() {
     project()
@@ -189,3 +245,203 @@ fn rust_version_older_than_edition() {
         )
         .run();
 }
+
+#[cargo_test]
+fn rust_version_allows() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            rust-version = "1.64"
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    let allows = |version| {
+        with_workspace(p.root().join("Cargo.toml"), |ws| {
+            let manifest = ws.current().unwrap().manifest();
+            manifest.rust_version_allows(&version)
+        })
+    };
+    assert_eq!(allows(Version::new(1, 65, 0)), Some(true));
+    assert_eq!(allows(Version::new(1, 60, 0)), Some(false));
+
+    p.change_file("Cargo.toml", &basic_manifest("foo", "0.0.1"));
+    assert_eq!(allows(Version::new(1, 65, 0)), None);
+}
+
+fn rust_version_range_project(
+    rust_version: &str,
+    cargo_features: bool,
+) -> cargo_test_support::Project {
+    let cargo_features = if cargo_features {
+        "cargo-features = [\"rust-version-ranges\"]"
+    } else {
+        ""
+    };
+    project()
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                {}
+                [package]
+                name = "foo"
+                version = "0.0.1"
+                authors = []
+                edition = "2021"
+                rust-version = "{}"
+                "#,
+                cargo_features, rust_version
+            ),
+        )
+        .file("src/lib.rs", "")
+        .build()
+}
+
+#[cargo_test]
+fn rust_version_range_lower_bound() {
+    let rustc = Version::parse(rustc_release()).unwrap();
+    rust_version_range_project(&format!(">={}.{}", rustc.major, rustc.minor), true)
+        .cargo("build")
+        .masquerade_as_nightly_cargo()
+        .run();
+}
+
+#[cargo_test]
+fn rust_version_range_compound() {
+    let rustc = Version::parse(rustc_release()).unwrap();
+    let rust_version = format!(">=1.56, <{}.{}", rustc.major, rustc.minor);
+    rust_version_range_project(&rust_version, true)
+        .cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(format!(
+            "\
+[ERROR] package `foo v0.0.1 ([..])` cannot be built because it requires \
+rustc `{}`, while the currently active rustc version is [..]
+",
+            rust_version
+        ))
+        .run();
+}
+
+#[cargo_test]
+fn rust_version_range_older_than_edition() {
+    rust_version_range_project(">=1.50, <1.80", true)
+        .cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  rust-version >=1.50, <1.80 is older than first version (1.56.0) required by \
+the specified edition (2021)
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn rust_version_range_requires_feature() {
+    rust_version_range_project(">=1.70", false)
+        .cargo("build")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[ERROR] failed to parse manifest at `[..]`
+
+Caused by:
+  `rust-version` must be a value like \"1.32\"
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn rust_version_range_env_is_lower_bound() {
+    let p = rust_version_range_project(">=1.56, <2", true);
+    p.change_file(
+        "src/main.rs",
+        r#"fn main() { println!("{}", env!("CARGO_PKG_RUST_VERSION")); }"#,
+    );
+    p.cargo("run -q")
+        .masquerade_as_nightly_cargo()
+        .with_stdout("1.56.0")
+        .run();
+}
+
+#[cargo_test]
+fn rust_version_range_cannot_be_packaged() {
+    rust_version_range_project(">=1.56, <2", true)
+        .cargo("package --no-verify")
+        .masquerade_as_nightly_cargo()
+        .with_status(101)
+        .with_stderr(
+            "\
+[WARNING] manifest has no description, license, license-file, documentation, homepage or repository.
+See https://doc.rust-lang.org/cargo/reference/manifest.html#package-metadata for more info.
+[ERROR] `rust-version` of \">=1.56, <2\" is a version requirement, which can't be published
+Set it to the lowest supported version, \"1.56.0\", before publishing.
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn lint_unpublished_rust_version() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            cargo-features = ["lint-unpublished-rust-version"]
+
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            rust-version = "1.60"
+            publish = false
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .masquerade_as_nightly_cargo()
+        .with_stderr(
+            "\
+[WARNING] `rust-version` is set, but the package is not published (`publish = false`), \
+so the field may be unnecessary
+[CHECKING] foo v0.0.1 ([CWD])
+[FINISHED] [..]
+",
+        )
+        .run();
+
+    // Without the feature the combination is left alone.
+    p.change_file(
+        "Cargo.toml",
+        r#"
+        [package]
+        name = "foo"
+        version = "0.0.1"
+        rust-version = "1.60"
+        publish = false
+        "#,
+    );
+    p.cargo("check")
+        .with_stderr(
+            "\
+[FINISHED] [..]
+",
+        )
+        .run();
+}
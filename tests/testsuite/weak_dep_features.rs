@@ -153,6 +153,71 @@ Caused by:
         .run();
 }
 
+#[cargo_test]
+fn missing_weak_dep() {
+    // Attempt to use dep_name?/feat where dep_name is not a dependency at all.
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [features]
+                feat = ["missing?/feat"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check")
+        .with_status(101)
+        .with_stderr(
+            "\
+error: failed to parse manifest at `[ROOT]/foo/Cargo.toml`
+
+Caused by:
+  feature `feat` includes `missing?/feat`, but `missing` is not a dependency
+",
+        )
+        .run();
+}
+
+#[cargo_test]
+fn all_feature_references_valid() {
+    // Every form of feature reference pointing at something that exists.
+    Package::new("dep", "1.0.0").feature("feat", &[]).publish();
+    Package::new("other", "1.0.0")
+        .feature("feat", &[])
+        .publish();
+
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+
+                [dependencies]
+                dep = { version = "1.0", optional = true }
+                other = "1.0"
+
+                [features]
+                base = []
+                uses-dep = ["base", "dep:dep"]
+                weak-feat = ["dep?/feat", "other/feat"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .build();
+
+    p.cargo("check --features uses-dep,weak-feat")
+        .with_stderr_does_not_contain("[WARNING][..]")
+        .run();
+}
+
 #[cargo_test]
 fn optional_cli_syntax() {
     // --features bar?/feat
@@ -2454,3 +2454,43 @@ fn virtual_primary_package_env_var() {
     p.cargo("clean").run();
     p.cargo("test -p foo").run();
 }
+
+#[cargo_test]
+fn members_glob_matching_nothing() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["crates/*", "tools/*"]
+            "#,
+        )
+        .file("crates/bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file("crates/bar/src/lib.rs", "")
+        .file("tools/README.md", "")
+        .build();
+
+    // `tools/*` only matches a file, so it adds no members.
+    p.cargo("check")
+        .with_stderr(
+            "\
+[WARNING] [CWD]/Cargo.toml: workspace member pattern `tools/*` in `workspace.members` \
+does not match any directory
+[CHECKING] bar v0.1.0 ([CWD]/crates/bar)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+
+    // Once the pattern matches a crate the warning goes away.
+    p.change_file("tools/baz/Cargo.toml", &basic_manifest("baz", "0.1.0"));
+    p.change_file("tools/baz/src/lib.rs", "");
+    p.cargo("check")
+        .with_stderr(
+            "\
+[CHECKING] baz v0.1.0 ([CWD]/tools/baz)
+[FINISHED] dev [unoptimized + debuginfo] target(s) in [..]
+",
+        )
+        .run();
+}